@@ -1,19 +1,26 @@
-use std::path::Path;
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
 
 use loss72_platemaker_construct::{copy_dir_recursively, copy_files, copy_individual_file};
 use loss72_platemaker_core::{
     fs::{Directory, File},
-    log, model::GenerationContext,
+    log,
+    model::{Article, GenerationContext, WithheldReason},
 };
-use loss72_platemaker_markdown::{MarkdownProcessError, parse_markdown};
+use loss72_platemaker_markdown::{FrontmatterSchema, MarkdownProcessError, parse_markdown};
 use loss72_platemaker_structure::{
-    ArticleFile, ArticleGroup, AssetFile, ContentDirectory,
+    ArticleFile, ArticleGroup, AssetFile, ContentDirectory, ContentDirectoryError,
+    git::{GitHistory, GitHistoryError},
     template::{is_template_file, template_file_paths},
 };
 use loss72_platemaker_website::{
-    WebsiteGenerationError, generate_article_html, generate_index_html, get_webpage_construction,
-    load_templates,
+    ArticleRelations, WebsiteGenerationError, find_template_dependencies, generate_ai_usage_pages,
+    generate_article_html, generate_author_pages, generate_index_html, generate_series_pages,
+    generate_tag_pages, generate_type_pages, get_webpage_construction, load_templates,
 };
+use loss72_platemaker_widgets::messages::MessageCatalog;
 
 use crate::{config::Configuration, error::report_error};
 
@@ -27,6 +34,12 @@ pub enum TaskError {
 
     #[error(transparent)]
     FileCopy(#[from] std::io::Error),
+
+    #[error(transparent)]
+    ContentDirectory(#[from] ContentDirectoryError),
+
+    #[error("Could not read the history of the articles: {0}")]
+    GitHistory(#[from] GitHistoryError),
 }
 
 pub type TaskResult<T> = Result<T, TaskError>;
@@ -34,12 +47,12 @@ pub type TaskResult<T> = Result<T, TaskError>;
 pub fn run_all_build_steps(config: &Configuration, ctx: &GenerationContext) -> TaskResult<()> {
     log!(job_start: "Building all articles in {}", config.article_md_dir.path().display());
 
-    let content_dir = ContentDirectory::new(&config.article_md_dir)?;
+    let content_dir = ContentDirectory::new(&config.article_md_dir, &config.default_language)?;
 
     log!(ok: "Discovered {} articles", content_dir.markdown_files.len());
 
     let result = Ok(())
-        .and_then(|_| build_files(config, &content_dir.markdown_files, true, &ctx))
+        .and_then(|_| build_files(config, &content_dir.markdown_files, true, ctx))
         .and_then(|_| copy_template_files(config))
         .and_then(|_| copy_asset_files(config, &content_dir.article_group));
 
@@ -56,20 +69,68 @@ pub fn build_files(
     full_build: bool,
     ctx: &GenerationContext,
 ) -> TaskResult<()> {
-    let mut files = files.iter().peekable();
-
-    if files.peek().is_none() {
+    if files.is_empty() {
         return Ok(());
     }
 
     log!(section: "Loading HTML from {}", config.html_template_dir.path().display());
-    let html_templates = load_templates(&config.html_template_dir)?;
+    let html_templates = load_templates(
+        &config.html_template_dir,
+        &config.widgets,
+        &config.article_types,
+        &config.default_article_type,
+        ctx,
+    )?;
 
-    let articles = files
-        .filter_map(|file| parse_markdown(file).inspect_err(report_error).ok())
-        .collect::<Vec<_>>();
+    let git_history = if config.git_history {
+        log!(section: "Reading the history of the articles from git");
+        Some(GitHistory::load(&config.article_md_dir)?)
+    } else {
+        None
+    };
+
+    let schema = config.frontmatter_schema();
+    let (articles, withheld) = parse_articles(
+        files,
+        &html_templates.messages,
+        &schema,
+        git_history.as_ref(),
+        ctx,
+    );
 
     log!(ok: "Built {} articles", articles.len());
+
+    // The other articles are needed for the navigation, and the pages showing the edited ones have to be updated
+    let related_articles = if full_build {
+        vec![]
+    } else {
+        log!(section: "Reading the other articles for the navigation");
+
+        let content_dir = ContentDirectory::new(&config.article_md_dir, &config.default_language)?;
+        parse_articles(
+            content_dir
+                .markdown_files
+                .iter()
+                .filter(|other| files.iter().all(|file| file.id != other.id)),
+            &html_templates.messages,
+            &schema,
+            git_history.as_ref(),
+            ctx,
+        )
+        .0
+    };
+
+    let relations = ArticleRelations::new(articles.iter().chain(&related_articles));
+    let related_articles = related_articles
+        .iter()
+        .filter(|other| {
+            articles
+                .iter()
+                .chain(withheld.iter().map(|(_, article)| article))
+                .any(|article| relations.is_referred_from(article, other))
+        })
+        .collect::<Vec<_>>();
+
     log!(section: "Generating HTML contents for articles");
 
     if ctx.release {
@@ -78,12 +139,13 @@ pub fn build_files(
 
     let htmls = articles
         .iter()
-        .map(|article| generate_article_html(&html_templates, article, ctx))
+        .chain(related_articles.iter().copied())
+        .map(|article| generate_article_html(&html_templates, article, &relations, ctx))
         .collect::<Result<Vec<_>, _>>();
 
     let mut htmls = match htmls {
         Ok(htmls) => {
-            log!(ok: "Generated all {} article pages", articles.len());
+            log!(ok: "Generated all {} article pages", htmls.len());
             htmls
         }
         Err(error) => {
@@ -93,35 +155,116 @@ pub fn build_files(
 
     htmls.sort_by(|left, right| left.article.id.cmp(&right.article.id).reverse());
 
-    let index_page = if full_build {
-        Some(generate_index_html(&html_templates, htmls.as_slice(), ctx)?)
-    } else {
-        None
-    };
+    let mut pages = vec![];
+    if full_build {
+        for lang in relations.languages() {
+            pages.push(generate_index_html(
+                &html_templates,
+                htmls.as_slice(),
+                lang,
+                ctx,
+            )?);
+        }
+        pages.extend(generate_tag_pages(&html_templates, htmls.as_slice(), ctx)?);
+        pages.extend(generate_series_pages(
+            &html_templates,
+            &relations,
+            htmls.as_slice(),
+            ctx,
+        )?);
+        pages.extend(generate_author_pages(
+            &html_templates,
+            htmls.as_slice(),
+            ctx,
+        )?);
+        pages.extend(generate_ai_usage_pages(
+            &html_templates,
+            htmls.as_slice(),
+            ctx,
+        )?);
+        pages.extend(generate_type_pages(&html_templates, htmls.as_slice(), ctx)?);
+    }
 
     log!(section: "Writing pages to the file system");
 
-    let construction = get_webpage_construction(index_page.as_ref(), htmls.as_slice());
+    let construction = get_webpage_construction(pages.as_slice(), htmls.as_slice());
     let plan = construction.plan(config.destination.path());
     plan.execute()?;
 
     log!(ok: "Wrote pages");
 
+    if !withheld.is_empty() {
+        log!(section: "Skipped {} articles in release build", withheld.len());
+        for (reason, article) in &withheld {
+            log!(step: "{} ({}): {}", article.id.slug, article.metadata.title, reason);
+        }
+    }
+
     Ok(())
 }
 
+/// Parses the articles, separating the ones withheld from the build. Articles failed to parse are reported and skipped.
+fn parse_articles<'a>(
+    files: impl IntoIterator<Item = &'a ArticleFile>,
+    catalog: &MessageCatalog,
+    schema: &FrontmatterSchema,
+    git_history: Option<&GitHistory>,
+    ctx: &GenerationContext,
+) -> (Vec<Article>, Vec<(WithheldReason, Article)>) {
+    let mut articles = vec![];
+    let mut withheld = vec![];
+
+    for file in files {
+        let Some(mut article) = parse_markdown(file, catalog, schema)
+            .inspect_err(report_error)
+            .ok()
+        else {
+            continue;
+        };
+
+        article.history = git_history
+            .and_then(|history| history.get(file.file()))
+            .cloned();
+
+        match article.withheld_reason(ctx) {
+            Some(reason) => withheld.push((reason, article)),
+            None => articles.push(article),
+        }
+    }
+
+    (articles, withheld)
+}
+
 pub fn copy_template_files(config: &Configuration) -> TaskResult<()> {
     log!(section: "Copying files in template directory");
 
-    copy_dir_recursively(
-        &config.html_template_dir,
-        &config.destination,
-        &template_file_paths(),
-    )?;
+    let excludes = template_file_paths()
+        .into_iter()
+        .chain(config.declared_template_paths())
+        .chain(template_dependency_paths(config)?)
+        .collect::<Vec<_>>();
+
+    copy_dir_recursively(&config.html_template_dir, &config.destination, &excludes)?;
 
     Ok(())
 }
 
+/// Partials and layouts used by the templates, which are not copied but rebuild the templates when changed.
+fn template_dependency_paths(config: &Configuration) -> TaskResult<BTreeSet<PathBuf>> {
+    let templates = template_file_paths()
+        .into_iter()
+        .chain(config.declared_template_paths())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "html")
+        });
+
+    Ok(find_template_dependencies(
+        &config.html_template_dir,
+        templates,
+    )?)
+}
+
 pub fn copy_asset_files(config: &Configuration, article_group: &[ArticleGroup]) -> TaskResult<()> {
     log!(section: "Copying asset files in article directory");
 
@@ -162,18 +305,34 @@ pub fn copy_individual_template_files(config: &Configuration, files: &[File], ct
 
     log!(job_start: "Updating template files");
 
-    if files.iter().any(|file| {
-        is_template_file(
-            file.path()
-                .strip_prefix(config.html_template_dir.path())
-                .unwrap_or(file.path()),
-        )
-    }) {
+    // Only the known templates trigger the rebuild if the dependencies cannot be found, so that the other files
+    // are still copied
+    let dependencies = template_dependency_paths(config)
+        .inspect_err(|err| {
+            log!(warn: "Partials and layouts of the templates could not be found: {}", err);
+        })
+        .unwrap_or_default();
+
+    // Templates are not copied, as in the full build
+    let (templates, others): (Vec<_>, Vec<_>) = files.iter().cloned().partition(|file| {
+        let path = file
+            .path()
+            .strip_prefix(config.html_template_dir.path())
+            .unwrap_or(file.path());
+
+        is_template_file(path)
+            || config
+                .declared_template_paths()
+                .any(|declared| declared == path)
+            || dependencies.contains(path)
+    });
+
+    if !templates.is_empty() {
         log!(warn: "Article page template file is updated! Rebuilding all articles.");
         run_all_build_steps(config, ctx)?;
     }
 
-    copy_files(&config.html_template_dir, &config.destination, files)?;
+    copy_files(&config.html_template_dir, &config.destination, &others)?;
 
     log!(job_end: "Updated template files");
 
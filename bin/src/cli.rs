@@ -1,9 +1,10 @@
 use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, FixedOffset, Utc};
 use clap::Parser;
-use loss72_platemaker_core::model::GenerationContext;
+use loss72_platemaker_core::{datetime::parse_datetime, log, model::GenerationContext};
 
-use crate::cmd::watch::WatchParam;
+use crate::{cmd::watch::WatchParam, config::Configuration};
 
 #[derive(Parser, Debug)]
 #[command()]
@@ -23,6 +24,10 @@ pub struct BuildArgs {
     /// Enable release build.
     #[arg(short, long)]
     pub release: bool,
+
+    /// Time regarded as now for scheduled articles, in RFC 3339. Defaults to $SOURCE_DATE_EPOCH or the current time.
+    #[arg(long, value_parser = parse_datetime)]
+    pub now: Option<DateTime<FixedOffset>>,
 }
 
 /// Watch for the file change, and update the website as necessary
@@ -40,6 +45,10 @@ pub struct WatchArgs {
     /// Enable release build.
     #[arg(short, long)]
     pub release: bool,
+
+    /// Time regarded as now for scheduled articles, in RFC 3339. Defaults to $SOURCE_DATE_EPOCH or the current time.
+    #[arg(long, value_parser = parse_datetime)]
+    pub now: Option<DateTime<FixedOffset>>,
 }
 
 impl Commands {
@@ -56,6 +65,29 @@ impl Commands {
             Commands::Watch(watch_args) => watch_args.release,
         }
     }
+
+    pub fn now(&self) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Commands::Build(build_args) => build_args.now,
+            Commands::Watch(watch_args) => watch_args.now,
+        }
+    }
+}
+
+fn source_date_epoch() -> Option<DateTime<Utc>> {
+    let epoch = std::env::var("SOURCE_DATE_EPOCH").ok()?;
+
+    let parsed = epoch
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .and_then(|epoch| DateTime::from_timestamp(epoch, 0));
+
+    if parsed.is_none() {
+        log!(warn: "SOURCE_DATE_EPOCH is not a valid UNIX timestamp and ignored: {}", epoch);
+    }
+
+    parsed
 }
 
 impl From<&WatchArgs> for WatchParam {
@@ -66,10 +98,17 @@ impl From<&WatchArgs> for WatchParam {
     }
 }
 
-impl From<&Commands> for GenerationContext {
-    fn from(value: &Commands) -> Self {
-        Self {
-            release: value.release(),
+impl Commands {
+    pub fn generation_context(&self, config: &Configuration) -> GenerationContext {
+        GenerationContext {
+            release: self.release(),
+            now: self
+                .now()
+                .map(|now| now.to_utc())
+                .or_else(source_date_epoch)
+                .unwrap_or_else(Utc::now),
+            default_language: config.default_language.clone(),
+            stale_after_days: config.stale_after_days,
         }
     }
 }
@@ -3,7 +3,7 @@ use std::{path::PathBuf, time::Duration};
 use crossbeam_channel::{RecvError, select, unbounded};
 
 use loss72_platemaker_core::{fs::File, log, model::GenerationContext};
-use loss72_platemaker_structure::{ArticleFile, AssetFile};
+use loss72_platemaker_structure::{ArticleFile, AssetFile, ContentDirectory};
 use notify::{EventKind, RecursiveMode};
 use notify_debouncer_full::{DebounceEventResult, new_debouncer};
 
@@ -70,9 +70,15 @@ pub fn watch_for_change(config: &Configuration, param: &WatchParam, ctx: &Genera
                     continue;
                 };
 
-                let articles = files.iter()
-                    .filter_map(|file| ArticleFile::from_file(file, &config.article_md_dir))
+                let mut articles = files.iter()
+                    .filter_map(|file| {
+                        ArticleFile::try_from_file(file, &config.article_md_dir, &config.default_language)
+                            .inspect_err(report_error)
+                            .ok()
+                            .flatten()
+                    })
                     .collect::<Vec<_>>();
+                articles.extend(articles_beside_bibliographies(config, &files, &articles));
 
                 build_files(config, &articles, false, ctx)
                     .inspect_err(report_error)
@@ -106,6 +112,43 @@ pub fn watch_for_change(config: &Configuration, param: &WatchParam, ctx: &Genera
     Ok(())
 }
 
+/// Articles in the same directory as the changed BibTeX files, since they may read them.
+fn articles_beside_bibliographies(
+    config: &Configuration,
+    files: &[File],
+    changed: &[ArticleFile],
+) -> Vec<ArticleFile> {
+    let directories = files
+        .iter()
+        .filter(|file| file.path().extension().is_some_and(|ext| ext == "bib"))
+        .filter_map(|file| file.path().parent())
+        .collect::<Vec<_>>();
+
+    if directories.is_empty() {
+        return vec![];
+    }
+
+    let Some(content_dir) = ContentDirectory::new(&config.article_md_dir, &config.default_language)
+        .inspect_err(report_error)
+        .ok()
+    else {
+        return vec![];
+    };
+
+    content_dir
+        .markdown_files
+        .into_iter()
+        .filter(|article| {
+            article
+                .file()
+                .path()
+                .parent()
+                .is_some_and(|dir| directories.contains(&dir))
+                && changed.iter().all(|changed| changed.id != article.id)
+        })
+        .collect()
+}
+
 fn handle_notify_event(received: Result<DebounceEventResult, RecvError>) -> Option<Vec<File>> {
     let events = match received {
         Ok(Ok(events)) => events,
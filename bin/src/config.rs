@@ -1,12 +1,43 @@
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
-use loss72_platemaker_core::fs::Directory;
+use loss72_platemaker_core::{
+    fs::Directory,
+    model::{default_language, default_stale_after_days},
+};
+use loss72_platemaker_markdown::FrontmatterSchema;
+use loss72_platemaker_widgets::{
+    article_type::{ArticleTypeDefinition, builtin_article_types, default_article_type},
+    user::UserWidgetDefinition,
+};
 
 #[derive(Debug, serde::Deserialize)]
 pub struct ConfigurationScheme {
     pub html_template_dir: PathBuf,
     pub article_md_dir: PathBuf,
     pub destination: PathBuf,
+    #[serde(default)]
+    pub git_history: bool,
+    #[serde(default = "default_language")]
+    pub default_language: String,
+    #[serde(default = "default_stale_after_days")]
+    pub stale_after_days: u32,
+    #[serde(default = "default_strict_frontmatter")]
+    pub strict_frontmatter: bool,
+    #[serde(default)]
+    pub widgets: BTreeMap<String, UserWidgetDefinition>,
+    #[serde(default)]
+    pub article_types: BTreeMap<String, ArticleTypeDefinition>,
+    #[serde(default = "default_article_type")]
+    pub default_article_type: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigurationError {
+    #[error("I/O Error: {0}")]
+    IOError(#[from] std::io::Error),
+
+    #[error("default_article_type '{0}' is not one of the article types")]
+    UnknownDefaultArticleType(String),
 }
 
 #[derive(Debug)]
@@ -14,16 +45,82 @@ pub struct Configuration {
     pub html_template_dir: Directory,
     pub article_md_dir: Directory,
     pub destination: Directory,
+    /// Read the commit history of the articles from the git repository containing `article_md_dir`.
+    pub git_history: bool,
+    /// Language of the articles without the language in their file names, such as `12_foo.md`.
+    pub default_language: String,
+    /// Days without updates after which the articles show the staleness notice,
+    /// if their types have `staleness_notice`. `0` disables it.
+    pub stale_after_days: u32,
+    /// Rejects the articles with unknown keys in the frontmatter. They are only warned if `false`.
+    pub strict_frontmatter: bool,
+    /// Widgets declared by the user, keyed by the name used in the frontmatter and as the placeholder.
+    pub widgets: BTreeMap<String, UserWidgetDefinition>,
+    /// Article types available as `widgets.article_type`. The built-in `Activity` and `Research` are used if empty.
+    pub article_types: BTreeMap<String, ArticleTypeDefinition>,
+    /// Type of the articles without `widgets.article_type`, `Activity` by default.
+    pub default_article_type: String,
 }
 
 impl TryFrom<ConfigurationScheme> for Configuration {
-    type Error = std::io::Error;
+    type Error = ConfigurationError;
 
     fn try_from(value: ConfigurationScheme) -> Result<Self, Self::Error> {
+        let is_declared = if value.article_types.is_empty() {
+            builtin_article_types().contains_key(&value.default_article_type)
+        } else {
+            value
+                .article_types
+                .contains_key(&value.default_article_type)
+        };
+        if !is_declared {
+            return Err(ConfigurationError::UnknownDefaultArticleType(
+                value.default_article_type,
+            ));
+        }
+
         Ok(Configuration {
             html_template_dir: Directory::new(value.html_template_dir)?,
             article_md_dir: Directory::new(value.article_md_dir)?,
             destination: Directory::new(value.destination)?,
+            git_history: value.git_history,
+            default_language: value.default_language,
+            stale_after_days: value.stale_after_days,
+            strict_frontmatter: value.strict_frontmatter,
+            widgets: value.widgets,
+            article_types: value.article_types,
+            default_article_type: value.default_article_type,
         })
     }
 }
+
+fn default_strict_frontmatter() -> bool {
+    true
+}
+
+impl Configuration {
+    pub fn frontmatter_schema(&self) -> FrontmatterSchema {
+        FrontmatterSchema {
+            user_widgets: self.widgets.keys().cloned().collect(),
+            article_types: if self.article_types.is_empty() {
+                builtin_article_types().into_keys().collect()
+            } else {
+                self.article_types.keys().cloned().collect()
+            },
+            strict: self.strict_frontmatter,
+        }
+    }
+
+    /// Templates declared in the configuration, relative to the template directory: snippets and styles
+    /// of the user widgets, and the list templates of the article types.
+    pub fn declared_template_paths(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.widgets
+            .values()
+            .flat_map(|widget| std::iter::once(widget.template.clone()).chain(widget.style.clone()))
+            .chain(
+                self.article_types
+                    .values()
+                    .filter_map(|article_type| article_type.list_template.clone()),
+            )
+    }
+}
@@ -27,8 +27,12 @@ fn main() -> Result<(), &'static str> {
 
         println!();
         match args {
-            Commands::Build(_) => build(&config, &(&args).into()),
-            Commands::Watch(ref watch_args) => watch(&config, &watch_args.into(), &(&args).into()),
+            Commands::Build(_) => build(&config, &args.generation_context(&config)),
+            Commands::Watch(ref watch_args) => watch(
+                &config,
+                &watch_args.into(),
+                &args.generation_context(&config),
+            ),
         }
     })
     .map_err(|_| "Failed due to the error above")
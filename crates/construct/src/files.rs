@@ -58,7 +58,7 @@ impl ConstructionPlan<'_> {
 }
 
 impl Construction<'_> {
-    pub fn plan(&self, root: &Path) -> ConstructionPlan {
+    pub fn plan(&self, root: &Path) -> ConstructionPlan<'_> {
         let mut plan = self._plan(root);
 
         plan.dirs.sort_by_key(|x| x.as_os_str().len());
@@ -66,7 +66,7 @@ impl Construction<'_> {
         plan
     }
 
-    fn _plan(&self, parent: &Path) -> ConstructionPlan {
+    fn _plan(&self, parent: &Path) -> ConstructionPlan<'_> {
         let root = parent.join(self.dir);
 
         let mut plan = ConstructionPlan {
@@ -162,6 +162,26 @@ mod tests {
             }],
         };
 
-        panic!("{:#?}", construction.plan(Path::new("/root")));
+        let plan = construction.plan(Path::new("/root"));
+
+        assert_eq!(
+            plan.files
+                .iter()
+                .map(|(path, content)| (path.to_string_lossy().to_string(), *content))
+                .collect::<Vec<_>>(),
+            [
+                ("/root/pages/1.html", "AAA"),
+                ("/root/pages/2.html", "AAA"),
+                ("/root/pages/3.html", "AAA"),
+                ("/root/pages/sub-1/1.html", "A"),
+                ("/root/pages/sub-1/2.html", "B"),
+                ("/root/pages/sub-1/3.html", "C"),
+            ]
+            .map(|(path, content)| (path.to_string(), content))
+        );
+        assert!(
+            plan.dirs
+                .contains(&Path::new("/root/pages/sub-1").to_path_buf())
+        );
     }
 }
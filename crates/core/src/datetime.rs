@@ -0,0 +1,110 @@
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Deserializer, de::Error};
+
+/// Accepts both of TOML's offset date-time (`2026-11-01T09:00:00+09:00`) and RFC 3339 strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TomlDateTime {
+    Native(toml::value::Datetime),
+    Text(String),
+}
+
+pub fn parse_datetime(text: &str) -> Result<DateTime<FixedOffset>, String> {
+    DateTime::parse_from_rfc3339(text).map_err(|e| {
+        format!("'{text}' is not a date-time with an offset (e.g. 2026-11-01T09:00:00+09:00): {e}")
+    })
+}
+
+pub fn parse_date(text: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map_err(|e| format!("'{text}' is not a date (e.g. 2026-11-01): {e}"))
+}
+
+pub fn deserialize_date<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let text = match TomlDateTime::deserialize(deserializer)? {
+        TomlDateTime::Native(datetime) => datetime.to_string(),
+        TomlDateTime::Text(text) => text,
+    };
+
+    parse_date(&text).map_err(D::Error::custom)
+}
+
+pub fn deserialize_optional_date<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Date(#[serde(deserialize_with = "deserialize_date")] NaiveDate);
+
+    Ok(Option::<Date>::deserialize(deserializer)?.map(|Date(date)| date))
+}
+
+pub fn deserialize_optional_datetime<'de, D>(
+    deserializer: D,
+) -> Result<Option<DateTime<FixedOffset>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let text = match Option::<TomlDateTime>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(TomlDateTime::Native(datetime)) => datetime.to_string(),
+        Some(TomlDateTime::Text(text)) => text,
+    };
+
+    parse_datetime(&text).map(Some).map_err(D::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Metadata {
+        #[serde(default, deserialize_with = "super::deserialize_optional_datetime")]
+        at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    }
+
+    #[test]
+    fn deserializes_native_and_string_datetime() {
+        for toml in [
+            "at = 2026-11-01T09:00:00+09:00",
+            "at = \"2026-11-01T09:00:00+09:00\"",
+        ] {
+            let metadata = toml::from_str::<Metadata>(toml).expect("datetime to be valid");
+            assert_eq!(metadata.at.map(|at| at.timestamp()), Some(1_793_491_200));
+        }
+
+        assert!(
+            toml::from_str::<Metadata>("")
+                .expect("field to be optional")
+                .at
+                .is_none()
+        );
+        assert!(toml::from_str::<Metadata>("at = 2026-11-01T09:00:00").is_err());
+    }
+
+    #[derive(Deserialize)]
+    struct Revision {
+        #[serde(default, deserialize_with = "super::deserialize_optional_date")]
+        on: Option<chrono::NaiveDate>,
+    }
+
+    #[test]
+    fn deserializes_native_and_string_date() {
+        for toml in ["on = 2026-11-01", "on = \"2026-11-01\""] {
+            let revision = toml::from_str::<Revision>(toml).expect("date to be valid");
+            assert_eq!(revision.on, chrono::NaiveDate::from_ymd_opt(2026, 11, 1));
+        }
+
+        assert!(
+            toml::from_str::<Revision>("")
+                .expect("field to be optional")
+                .on
+                .is_none()
+        );
+        assert!(toml::from_str::<Revision>("on = 2026-13-01").is_err());
+    }
+}
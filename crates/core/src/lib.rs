@@ -1,5 +1,6 @@
 #![deny(clippy::unwrap_used)]
 
+pub mod datetime;
 pub mod fs;
 pub mod log;
 pub mod model;
@@ -1,26 +1,78 @@
 use std::any::type_name;
 
-use loss72_platemaker_widgets::Widgets;
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use loss72_platemaker_widgets::{
+    Widgets,
+    changelog::{Changelog, Revision},
+    staleness::{Age, Staleness},
+};
 use serde::Deserialize;
 
-use crate::util::get_slice_by_char;
+use crate::{
+    datetime::{deserialize_date, deserialize_optional_date, deserialize_optional_datetime},
+    util::get_slice_by_char,
+};
 
 #[derive(Clone, Deserialize, Debug)]
 pub struct GenerationContext {
     #[serde(default)]
     pub release: bool,
+
+    /// The time regarded as "now" while generating, e.g. for deciding scheduled articles to publish.
+    #[serde(default = "Utc::now")]
+    pub now: DateTime<Utc>,
+
+    /// Language of the articles without the language in their file names.
+    #[serde(default = "default_language")]
+    pub default_language: String,
+
+    /// Articles not updated for this many days since `now` show the staleness notice. `0` disables it.
+    #[serde(default = "default_stale_after_days")]
+    pub stale_after_days: u32,
+}
+
+pub fn default_language() -> String {
+    "ja".to_string()
+}
+
+pub fn default_stale_after_days() -> u32 {
+    730
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ArticleIdentifier {
     pub group: String,
     pub slug: String,
-    pub date: (u32, u8, u8),
+    pub date: NaiveDate,
+    /// Order among the articles on the same day, given as `$day-$seq_$slug.md`.
+    pub seq: Option<u32>,
+    /// Language of the translation, given as `$day_$slug.$lang.md`. `None` is the default language.
+    pub lang: Option<String>,
+}
+
+impl ArticleIdentifier {
+    /// Returns `true` if both are the translations of the same article, including the identical one.
+    pub fn is_translation_of(&self, other: &Self) -> bool {
+        self.group == other.group
+            && self.slug == other.slug
+            && self.date == other.date
+            && self.seq == other.seq
+    }
+}
+
+impl PartialOrd for ArticleIdentifier {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl Ord for ArticleIdentifier {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.date.cmp(&other.date).then(self.slug.cmp(&other.slug))
+        self.date
+            .cmp(&other.date)
+            .then(self.seq.cmp(&other.seq))
+            .then(self.slug.cmp(&other.slug))
+            .then(self.lang.cmp(&other.lang))
     }
 }
 
@@ -29,6 +81,60 @@ pub struct Article {
     pub id: ArticleIdentifier,
     pub metadata: ArticleMetadata,
     pub content: String,
+    pub outline: DocumentOutline,
+    pub history: Option<FileHistory>,
+}
+
+/// Why an article is kept out of the generated website.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WithheldReason {
+    Draft,
+    Scheduled(DateTime<FixedOffset>),
+}
+
+impl std::fmt::Display for WithheldReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WithheldReason::Draft => write!(f, "draft"),
+            WithheldReason::Scheduled(at) => write!(f, "scheduled at {}", at.to_rfc3339()),
+        }
+    }
+}
+
+impl Article {
+    /// Locale for the messages, or `None` for the default one.
+    pub fn locale(&self) -> Option<&str> {
+        self.metadata.locale.as_deref().or(self.id.lang.as_deref())
+    }
+
+    /// Returns the reason if the article should not be published in this generation.
+    /// Only release builds withhold articles.
+    pub fn withheld_reason(&self, ctx: &GenerationContext) -> Option<WithheldReason> {
+        if !ctx.release {
+            return None;
+        }
+
+        if self.metadata.draft {
+            return Some(WithheldReason::Draft);
+        }
+
+        match self.metadata.publish_at {
+            Some(publish_at) if publish_at > ctx.now => Some(WithheldReason::Scheduled(publish_at)),
+            _ => None,
+        }
+    }
+
+    /// Age of the article counted from the last update, compared with the threshold in `ctx`.
+    /// Whether to show it depends on the article type, so the caller has to decide.
+    pub fn staleness_widget(&self, ctx: &GenerationContext) -> Staleness {
+        let written = self.metadata.last_updated().unwrap_or(self.id.date);
+        let days = (ctx.now.date_naive() - written).num_days();
+        let is_stale = ctx.stale_after_days > 0 && days >= i64::from(ctx.stale_after_days);
+
+        Staleness {
+            age: is_stale.then(|| Age::from_days(days)),
+        }
+    }
 }
 
 impl std::fmt::Debug for Article {
@@ -39,6 +145,8 @@ impl std::fmt::Debug for Article {
         f.debug_struct(type_name)
             .field("id", &self.id)
             .field("metadata", &self.metadata)
+            .field("outline", &self.outline)
+            .field("history", &self.history)
             .field(
                 "content",
                 &format_args!(
@@ -55,6 +163,138 @@ pub struct ArticleMetadata {
     pub title: String,
     pub brief: String,
     #[serde(default)]
+    pub tags: Vec<String>,
+    /// Locale of the messages shown by the widgets, overriding the language of the file.
+    pub locale: Option<String>,
+    /// Keys of the authors registered in `authors.toml`.
+    #[serde(default)]
+    pub authors: Vec<String>,
+    #[serde(default)]
+    pub draft: bool,
+    /// Published, but left out of the listings and the previous/next links.
+    #[serde(default)]
+    pub unlisted: bool,
+    #[serde(default, deserialize_with = "deserialize_optional_datetime")]
+    pub publish_at: Option<DateTime<FixedOffset>>,
+    #[serde(default, deserialize_with = "deserialize_optional_date")]
+    pub updated: Option<NaiveDate>,
+    #[serde(default)]
+    pub changelog: Vec<ChangelogEntry>,
+    /// Name of the series the article belongs to.
+    pub series: Option<String>,
+    /// Position in the series. Parts without it follow the numbered ones in date order.
+    pub series_order: Option<u32>,
+    #[serde(default)]
     pub widgets: Widgets,
+    /// Arbitrary values for the templates, such as `${extra.hero_color}`.
+    #[serde(default)]
+    pub extra: toml::Table,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct ChangelogEntry {
+    #[serde(deserialize_with = "deserialize_date")]
+    pub date: NaiveDate,
+    pub summary: String,
+}
+
+impl ChangelogEntry {
+    pub const FIELDS: &[&str] = &["date", "summary"];
+}
+
+impl ArticleMetadata {
+    /// Keys of the frontmatter. Keep in sync with the fields.
+    pub const FIELDS: &[&str] = &[
+        "title",
+        "brief",
+        "tags",
+        "locale",
+        "authors",
+        "draft",
+        "unlisted",
+        "publish_at",
+        "updated",
+        "changelog",
+        "series",
+        "series_order",
+        "widgets",
+        "extra",
+    ];
+
+    /// The date the article was last revised: `updated`, or the latest date in `changelog`.
+    /// Feeds and sitemaps should prefer this over the date of the article when present.
+    pub fn last_updated(&self) -> Option<NaiveDate> {
+        self.updated
+            .into_iter()
+            .chain(self.changelog.iter().map(|entry| entry.date))
+            .max()
+    }
+
+    /// Looks up `extra` by the dotted key such as `sponsor.name`.
+    pub fn extra_value(&self, key: &str) -> Option<&toml::Value> {
+        let mut keys = key.split('.');
+        let first = self.extra.get(keys.next()?)?;
+
+        keys.try_fold(first, |value, key| value.as_table()?.get(key))
+    }
+
+    pub fn changelog_widget(&self) -> Changelog {
+        let mut entries = self.changelog.iter().collect::<Vec<_>>();
+        entries.sort_by(|left, right| left.date.cmp(&right.date).reverse());
+
+        Changelog(
+            entries
+                .into_iter()
+                .map(|entry| Revision {
+                    date: entry.date.format("%Y-%m-%d").to_string(),
+                    summary: entry.summary.clone(),
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Commit history of the article file in the git repository.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileHistory {
+    /// Time of the first commit containing the file.
+    pub created: DateTime<FixedOffset>,
+    /// Time of the last commit changing the file.
+    pub updated: DateTime<FixedOffset>,
+    /// Short hash of the last commit changing the file.
+    pub commit: String,
+    /// Path to the file from the repository root.
+    pub path: String,
 }
 
+/// Structure of the article collected while parsing, so that later stages need not re-parse the HTML.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DocumentOutline {
+    pub headings: Vec<OutlineHeading>,
+    pub images: Vec<OutlineImage>,
+    pub links: Vec<String>,
+    pub code_languages: Vec<String>,
+    pub footnote_count: usize,
+}
+
+impl DocumentOutline {
+    /// Links pointing to the other sites.
+    pub fn outbound_links(&self) -> impl Iterator<Item = &str> {
+        self.links.iter().map(|link| link.as_str()).filter(|link| {
+            link.starts_with("http://") || link.starts_with("https://") || link.starts_with("//")
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutlineHeading {
+    pub level: u8,
+    pub text: String,
+    pub id: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutlineImage {
+    pub url: String,
+    pub alt: String,
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use loss72_platemaker_widgets::sources::Source;
+
+/// Reads the entries of a BibTeX file as the sources.
+///
+/// Only the fields shown in the sources are read: `title`, `author` (separated by `and`), `year`,
+/// `url`, `urldate` and `archiveurl`. `@comment`, `@string` and `@preamble` are skipped, and the
+/// string macros are not expanded. Text outside the entries is ignored, including `@` not starting an entry.
+pub fn parse_bibtex(content: &str) -> Result<Vec<Source>, String> {
+    let mut reader = Reader { rest: content };
+    let mut sources = vec![];
+
+    while reader.skip_to('@') {
+        let entry_type = reader
+            .take_while(|char| char.is_ascii_alphanumeric())
+            .to_lowercase();
+        reader.skip_whitespace();
+        if entry_type.is_empty() || !(reader.eat('{') || reader.eat('(')) {
+            continue;
+        }
+
+        if matches!(entry_type.as_str(), "comment" | "string" | "preamble") {
+            reader.skip_entry_body();
+            continue;
+        }
+
+        let key = reader
+            .take_while(|char| char != ',' && char != '}' && char != ')')
+            .trim()
+            .to_string();
+        if key.is_empty() {
+            return Err(format!("An `@{entry_type}` entry has no key"));
+        }
+
+        let fields = reader
+            .fields()
+            .map_err(|reason| format!("In the entry `{key}`: {reason}"))?;
+        sources.push(to_source(key, fields)?);
+    }
+
+    Ok(sources)
+}
+
+fn to_source(key: String, mut fields: HashMap<String, String>) -> Result<Source, String> {
+    let Some(name) = fields.remove("title") else {
+        return Err(format!("The entry `{key}` has no title"));
+    };
+
+    let accessed = fields
+        .remove("urldate")
+        .map(|date| {
+            date.parse::<toml::value::Datetime>()
+                .map_err(|_| format!("The urldate of the entry `{key}` is not a date: {date}"))
+        })
+        .transpose()?;
+
+    Ok(Source {
+        name,
+        url: fields.remove("url"),
+        authors: fields
+            .remove("author")
+            .map(|authors| {
+                authors
+                    .split(" and ")
+                    .map(|author| author.trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        year: fields.remove("year").and_then(|year| year.parse().ok()),
+        accessed,
+        archive_url: fields.remove("archiveurl"),
+        key: Some(key),
+    })
+}
+
+struct Reader<'c> {
+    rest: &'c str,
+}
+
+impl<'c> Reader<'c> {
+    /// Moves to right after `target`, and returns `false` if it is not found.
+    fn skip_to(&mut self, target: char) -> bool {
+        match self.rest.find(target) {
+            Some(index) => {
+                self.rest = &self.rest[index + target.len_utf8()..];
+                true
+            }
+            None => {
+                self.rest = "";
+                false
+            }
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'c str {
+        let end = self
+            .rest
+            .find(|char| !predicate(char))
+            .unwrap_or(self.rest.len());
+        let (taken, rest) = self.rest.split_at(end);
+        self.rest = rest;
+
+        taken
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        match self.rest.strip_prefix(expected) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn skip_entry_body(&mut self) {
+        let mut depth = 1;
+        for (index, char) in self.rest.char_indices() {
+            match char {
+                '{' | '(' => depth += 1,
+                '}' | ')' => depth -= 1,
+                _ => {}
+            }
+
+            if depth == 0 {
+                self.rest = &self.rest[index + 1..];
+                return;
+            }
+        }
+
+        self.rest = "";
+    }
+
+    /// Reads `name = value` separated by commas until the end of the entry.
+    fn fields(&mut self) -> Result<HashMap<String, String>, String> {
+        let mut fields = HashMap::new();
+
+        loop {
+            self.skip_whitespace();
+            self.eat(',');
+            self.skip_whitespace();
+            if self.eat('}') || self.eat(')') {
+                return Ok(fields);
+            }
+            if self.rest.is_empty() {
+                return Err("The entry is not closed".to_string());
+            }
+
+            let name = self
+                .take_while(|char| char.is_ascii_alphanumeric() || char == '_' || char == '-')
+                .to_lowercase();
+            self.skip_whitespace();
+            if name.is_empty() || !self.eat('=') {
+                return Err(format!(
+                    "Expected `name = value` at: {}",
+                    self.rest.lines().next().unwrap_or_default()
+                ));
+            }
+            self.skip_whitespace();
+
+            let value = self
+                .value()
+                .ok_or_else(|| format!("The value of `{name}` is not closed"))?;
+            fields.insert(name, value.split_whitespace().collect::<Vec<_>>().join(" "));
+        }
+    }
+
+    /// Reads `{...}`, `"..."` or a bare word, removing the braces inside.
+    fn value(&mut self) -> Option<String> {
+        let closing = if self.eat('{') {
+            '}'
+        } else if self.eat('"') {
+            '"'
+        } else {
+            return Some(
+                self.take_while(|char| char.is_ascii_alphanumeric())
+                    .to_string(),
+            );
+        };
+
+        let mut depth = 0;
+        let mut value = String::new();
+        for (index, char) in self.rest.char_indices() {
+            match char {
+                char if char == closing && depth == 0 => {
+                    self.rest = &self.rest[index + 1..];
+                    return Some(value);
+                }
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                char => value.push(char),
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_bibtex;
+
+    #[test]
+    fn parses_entries() {
+        let sources = parse_bibtex(
+            r#"
+            % contact: foo@bar.com, or @ the office
+            @comment{ignored, {nested}}
+            @book{rust,
+              title = {The {Rust} Programming
+                       Language},
+              author = "Steve Klabnik and Carol Nichols",
+              year = 2023,
+            }
+            @online{docs, title = {Docs}, url = {https://docs.rs}, urldate = {2025-01-02}}
+            "#,
+        )
+        .expect("valid BibTeX");
+
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].key.as_deref(), Some("rust"));
+        assert_eq!(sources[0].name, "The Rust Programming Language");
+        assert_eq!(sources[0].authors, ["Steve Klabnik", "Carol Nichols"]);
+        assert_eq!(sources[0].year, Some(2023));
+        assert_eq!(sources[1].url.as_deref(), Some("https://docs.rs"));
+        assert_eq!(
+            sources[1].accessed.map(|date| date.to_string()).as_deref(),
+            Some("2025-01-02")
+        );
+    }
+
+    #[test]
+    fn rejects_entries_without_title() {
+        assert!(parse_bibtex("@misc{untitled, year = 2020}").is_err());
+    }
+}
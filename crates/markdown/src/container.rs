@@ -0,0 +1,113 @@
+use std::borrow::Cow;
+
+use loss72_platemaker_widgets::{Widget, messages::Messages, spoiler::Spoiler};
+
+const SPOILER_OPEN: &str = ":::spoiler";
+const CONTAINER_CLOSE: &str = ":::";
+
+/// Replaces the container lines below with HTML blocks before parsing, leaving the lines inside as they are.
+///
+/// ```markdown
+/// :::spoiler The ending
+/// Markdown shown only after the summary is clicked.
+/// :::
+/// ```
+///
+/// Lines in fenced code blocks are not touched. Containers left open are closed at the end.
+pub fn expand_containers<'c>(content: &'c str, messages: &Messages) -> Cow<'c, str> {
+    if !content.contains(SPOILER_OPEN) {
+        return Cow::Borrowed(content);
+    }
+
+    let mut expanded = String::with_capacity(content.len());
+    let mut fence: Option<&str> = None;
+    let mut depth = 0;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+
+        if let Some(opening) = fence {
+            if trimmed.trim_start().starts_with(opening) {
+                fence = None;
+            }
+            expanded.push_str(line);
+            continue;
+        }
+
+        if let Some(opening) = code_fence(trimmed) {
+            fence = Some(opening);
+            expanded.push_str(line);
+            continue;
+        }
+
+        if let Some(summary) = trimmed.strip_prefix(SPOILER_OPEN) {
+            let summary = summary.trim();
+            let spoiler = Spoiler {
+                summary: (!summary.is_empty()).then(|| summary.to_string()),
+            };
+
+            // Blank lines end the HTML block, so that the inside is parsed as Markdown
+            expanded.push('\n');
+            expanded.push_str(&spoiler.build(messages));
+            expanded.push_str("\n\n");
+            depth += 1;
+        } else if trimmed == CONTAINER_CLOSE && depth > 0 {
+            expanded.push('\n');
+            expanded.push_str(Spoiler::CLOSE);
+            expanded.push_str("\n\n");
+            depth -= 1;
+        } else {
+            expanded.push_str(line);
+        }
+    }
+
+    for _ in 0..depth {
+        expanded.push_str("\n\n");
+        expanded.push_str(Spoiler::CLOSE);
+        expanded.push('\n');
+    }
+
+    Cow::Owned(expanded)
+}
+
+/// Returns the fence if the line opens a fenced code block.
+fn code_fence(line: &str) -> Option<&'static str> {
+    let line = line.trim_start();
+
+    if line.starts_with("```") {
+        Some("```")
+    } else if line.starts_with("~~~") {
+        Some("~~~")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use loss72_platemaker_widgets::messages::MessageCatalog;
+
+    use super::expand_containers;
+
+    #[test]
+    fn expands_spoilers_outside_code_blocks() {
+        let catalog = MessageCatalog::new("en");
+        let content =
+            "before\n:::spoiler\nhidden\n:::\n```\n:::spoiler\n```\n:::spoiler Ending\nopen";
+        let expanded = expand_containers(content, &catalog.messages(None));
+
+        assert_eq!(
+            expanded,
+            "before\n\n<details class=\"spoiler\"><summary>Show spoiler</summary>\n\nhidden\n\n</details>\n\n\
+             ```\n:::spoiler\n```\n\n<details class=\"spoiler\"><summary>Ending</summary>\n\nopen\n\n</details>\n"
+        );
+    }
+
+    #[test]
+    fn leaves_content_without_containers() {
+        let catalog = MessageCatalog::default();
+        let content = "text\n:::\n";
+
+        assert_eq!(expand_containers(content, &catalog.messages(None)), content);
+    }
+}
@@ -1,7 +1,213 @@
-use loss72_platemaker_core::model::ArticleMetadata;
+use loss72_platemaker_core::model::{ArticleMetadata, ChangelogEntry};
+use loss72_platemaker_widgets::{Widgets, content_warning::ContentWarning, sources::Source};
 
 use super::parse::{ParseError, ParseResult};
 
 pub fn parse_toml_to_metadata(toml: &str) -> ParseResult<ArticleMetadata> {
     toml::from_str::<ArticleMetadata>(toml).map_err(|e| ParseError::InvalidToml(format!("{e}")))
 }
+
+/// Keys the frontmatter may have in addition to the built-in ones, and how to treat the unknown keys.
+#[derive(Clone, Debug)]
+pub struct FrontmatterSchema {
+    /// Names of the user widgets declared in the configuration.
+    pub user_widgets: Vec<String>,
+    /// Names of the article types available as `widgets.article_type`. Not checked if empty.
+    pub article_types: Vec<String>,
+    /// Rejects the articles with unknown keys. They are only warned otherwise.
+    pub strict: bool,
+}
+
+impl Default for FrontmatterSchema {
+    fn default() -> Self {
+        Self {
+            user_widgets: vec![],
+            article_types: vec![],
+            strict: true,
+        }
+    }
+}
+
+/// Lists every key which would be silently ignored in parsing `toml` into the metadata, and the article type
+/// not declared, with the similar known ones if any. The other values are left to the parser.
+pub fn find_unknown_keys(toml: &str, schema: &FrontmatterSchema) -> Vec<String> {
+    let Ok(table) = toml::from_str::<toml::Table>(toml) else {
+        return vec![];
+    };
+
+    let mut unknown_keys = vec![];
+    check_keys(&table, "", ArticleMetadata::FIELDS, &mut unknown_keys);
+
+    if let Some(toml::Value::Array(changelog)) = table.get("changelog") {
+        for (index, entry) in changelog.iter().enumerate() {
+            if let toml::Value::Table(entry) = entry {
+                check_keys(
+                    entry,
+                    &format!("changelog[{index}]."),
+                    ChangelogEntry::FIELDS,
+                    &mut unknown_keys,
+                );
+            }
+        }
+    }
+
+    if let Some(toml::Value::Table(widgets)) = table.get("widgets") {
+        let widget_names = Widgets::FIELDS
+            .iter()
+            .copied()
+            .chain(schema.user_widgets.iter().map(String::as_str))
+            .collect::<Vec<_>>();
+        check_keys(widgets, "widgets.", &widget_names, &mut unknown_keys);
+
+        if let Some(toml::Value::String(article_type)) = widgets.get("article_type") {
+            let article_types = schema
+                .article_types
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            if !article_types.is_empty() && !article_types.contains(&article_type.as_str()) {
+                unknown_keys.push(match suggest(article_type, &article_types) {
+                    Some(suggestion) => format!(
+                        "unknown article type `{article_type}` (did you mean `{suggestion}`?)"
+                    ),
+                    None => format!(
+                        "unknown article type `{article_type}` (available: {})",
+                        article_types.join(", ")
+                    ),
+                });
+            }
+        }
+
+        if let Some(toml::Value::Table(content_warning)) = widgets.get("content_warning") {
+            check_keys(
+                content_warning,
+                "widgets.content_warning.",
+                ContentWarning::FIELDS,
+                &mut unknown_keys,
+            );
+        }
+
+        if let Some(toml::Value::Array(sources)) = widgets.get("sources") {
+            for (index, source) in sources.iter().enumerate() {
+                if let toml::Value::Table(source) = source {
+                    check_keys(
+                        source,
+                        &format!("widgets.sources[{index}]."),
+                        Source::FIELDS,
+                        &mut unknown_keys,
+                    );
+                }
+            }
+        }
+    }
+
+    unknown_keys
+}
+
+fn check_keys(
+    table: &toml::Table,
+    prefix: &str,
+    known_keys: &[&str],
+    unknown_keys: &mut Vec<String>,
+) {
+    for key in table
+        .keys()
+        .filter(|key| !known_keys.contains(&key.as_str()))
+    {
+        match suggest(key, known_keys) {
+            Some(suggestion) => unknown_keys.push(format!(
+                "unknown key `{prefix}{key}` (did you mean `{prefix}{suggestion}`?)"
+            )),
+            None => unknown_keys.push(format!("unknown key `{prefix}{key}`")),
+        }
+    }
+}
+
+/// The known key closest to `key`, if it looks like a typo of it.
+fn suggest<'k>(key: &str, known_keys: &[&'k str]) -> Option<&'k str> {
+    let max_distance = key.chars().count().max(3) / 3;
+
+    known_keys
+        .iter()
+        .map(|known| (strsim::damerau_levenshtein(key, known), *known))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, known)| known)
+}
+
+/// Reads the frontmatter without parsing the document, since the sub parsers have to know
+/// some of it (e.g. `locale`) before they start.
+pub fn peek_frontmatter(content: &str) -> Option<toml::Table> {
+    let (frontmatter, _) = content
+        .trim_start()
+        .strip_prefix("+++")?
+        .split_once("\n+++")?;
+
+    toml::from_str::<toml::Table>(frontmatter).ok()
+}
+
+pub fn peek_locale(frontmatter: &toml::Table) -> Option<String> {
+    frontmatter.get("locale")?.as_str().map(str::to_string)
+}
+
+/// Keys of `widgets.sources`, `None` for the sources without keys, and the path of `widgets.bibliography`.
+pub fn peek_sources(frontmatter: &toml::Table) -> (Vec<Option<String>>, Option<String>) {
+    let Some(widgets) = frontmatter.get("widgets").and_then(toml::Value::as_table) else {
+        return (vec![], None);
+    };
+
+    let keys = widgets
+        .get("sources")
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|source| source.get("key")?.as_str().map(str::to_string))
+        .collect();
+    let bibliography = widgets
+        .get("bibliography")
+        .and_then(toml::Value::as_str)
+        .map(str::to_string);
+
+    (keys, bibliography)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FrontmatterSchema, find_unknown_keys};
+
+    #[test]
+    fn reports_unknown_keys_with_suggestions() {
+        let schema = FrontmatterSchema {
+            user_widgets: vec!["callout".to_string()],
+            article_types: vec!["Activity".to_string(), "Research".to_string()],
+            ..Default::default()
+        };
+        let toml = r#"
+            title = "Title"
+            breif = "Brief"
+            widget.ai = "Review"
+            extra_key = 1
+
+            [widgets]
+            article_type = "Reserch"
+            calout = {}
+            callout = {}
+
+            [[changelog]]
+            date = 2025-01-01
+            sumary = "Fixed"
+        "#;
+
+        assert_eq!(
+            find_unknown_keys(toml, &schema),
+            vec![
+                "unknown key `breif` (did you mean `brief`?)",
+                "unknown key `extra_key`",
+                "unknown key `widget` (did you mean `widgets`?)",
+                "unknown key `changelog[0].sumary` (did you mean `changelog[0].summary`?)",
+                "unknown key `widgets.calout` (did you mean `widgets.callout`?)",
+                "unknown article type `Reserch` (did you mean `Research`?)",
+            ]
+        );
+    }
+}
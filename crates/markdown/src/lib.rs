@@ -2,11 +2,28 @@
 
 use loss72_platemaker_core::{fs::File, log, model::Article};
 use loss72_platemaker_structure::ArticleFile;
-use parse::{ParseError, make_article_from_markdown};
+use std::{collections::BTreeSet, path::PathBuf};
 
+use bibtex::parse_bibtex;
+use container::expand_containers;
+use frontmatter::{peek_frontmatter, peek_locale, peek_sources};
+use loss72_platemaker_widgets::{messages::MessageCatalog, sources::Source};
+use parse::{ParseError, make_article_from_markdown_with};
+
+mod bibtex;
+mod container;
 mod frontmatter;
 mod parse;
 
+pub use frontmatter::FrontmatterSchema;
+pub use parse::{
+    control,
+    full_service::{MarkdownParseResult, MarkdownParser},
+    sub_parser::{
+        CitationSubParser, Citations, SubParser, SubParserHandle, SubParserOutputs, SubParsers,
+    },
+};
+
 #[derive(Debug, thiserror::Error)]
 pub enum MarkdownProcessError {
     #[error("Error during I/O: {0}")]
@@ -14,15 +31,106 @@ pub enum MarkdownProcessError {
 
     #[error("Error during parsing Markdown:\n{0}")]
     ParseError(ParseError),
+
+    #[error("Error during reading the bibliography {}:\n{reason}", .path.display())]
+    InvalidBibliography { path: PathBuf, reason: String },
+
+    #[error("These source keys are defined more than once: {}", .0.join(", "))]
+    DuplicateSourceKeys(Vec<String>),
 }
 
 pub fn is_markdown_path(file: &File) -> bool {
     file.path().extension().is_some_and(|ext| ext == "md")
 }
 
-pub fn parse_markdown(file: &ArticleFile) -> Result<Article, MarkdownProcessError> {
+/// Parses the article, using the messages in the locale of the article from `catalog`.
+pub fn parse_markdown(
+    file: &ArticleFile,
+    catalog: &MessageCatalog,
+    schema: &FrontmatterSchema,
+) -> Result<Article, MarkdownProcessError> {
+    parse_markdown_with(file, catalog, schema, |_| ()).map(|(article, _, _)| article)
+}
+
+/// Parses the article with additional sub parsers registered by `extend`.
+///
+/// `extend` receives the default set of sub parsers, and the value it returns (typically
+/// [`SubParserHandle`]s) is given back along with the outputs of every sub parser.
+pub fn parse_markdown_with<H>(
+    file: &ArticleFile,
+    catalog: &MessageCatalog,
+    schema: &FrontmatterSchema,
+    extend: impl for<'p> FnOnce(&mut SubParsers<'p>) -> H,
+) -> Result<(Article, H, SubParserOutputs), MarkdownProcessError> {
     log!(step: "Parsing ./{}", file.relative_path.display());
 
-    make_article_from_markdown(file, &file.file().read_to_string()?)
-        .map_err(MarkdownProcessError::ParseError)
+    let content = file.file().read_to_string()?;
+
+    let frontmatter = peek_frontmatter(&content).unwrap_or_default();
+
+    let locale = peek_locale(&frontmatter).or_else(|| file.id.lang.clone());
+    let messages = catalog.messages(locale.as_deref());
+
+    let (mut source_keys, bibliography) = peek_sources(&frontmatter);
+    let bibliography = bibliography
+        .map(|path| load_bibliography(file, path))
+        .transpose()?
+        .unwrap_or_default();
+    source_keys.extend(bibliography.iter().map(|source| source.key.clone()));
+
+    // Sources sharing the key would have the same anchor
+    let mut defined_keys = BTreeSet::new();
+    let duplicate_keys = source_keys
+        .iter()
+        .flatten()
+        .filter(|key| !defined_keys.insert(*key))
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    if !duplicate_keys.is_empty() {
+        return Err(MarkdownProcessError::DuplicateSourceKeys(
+            duplicate_keys.into_iter().collect(),
+        ));
+    }
+
+    let content = expand_containers(&content, &messages);
+    let mut sub_parsers = SubParsers::with_messages(&messages);
+    let citations = sub_parsers.push(CitationSubParser::new(source_keys.clone()));
+    let handles = extend(&mut sub_parsers);
+
+    let (mut article, mut outputs) =
+        make_article_from_markdown_with(file, &content, sub_parsers, schema)
+            .map_err(MarkdownProcessError::ParseError)?;
+    article.metadata.widgets.sources.0.extend(bibliography);
+
+    let citations = outputs.take(citations).unwrap_or_default();
+    for key in &citations.undefined {
+        log!(warn: "[@{}] does not match any source", key);
+    }
+    for key in source_keys
+        .iter()
+        .flatten()
+        .filter(|key| !citations.cited.contains(key))
+    {
+        log!(warn: "Source `{}` is not cited", key);
+    }
+
+    Ok((article, handles, outputs))
+}
+
+/// Reads the BibTeX file at `path` relative to the directory of the article.
+fn load_bibliography(
+    file: &ArticleFile,
+    path: String,
+) -> Result<Vec<Source>, MarkdownProcessError> {
+    let path = file
+        .file()
+        .path()
+        .parent()
+        .map(|dir| dir.join(&path))
+        .unwrap_or_else(|| PathBuf::from(path));
+
+    let content = std::fs::read_to_string(&path)?;
+
+    parse_bibtex(&content)
+        .map_err(|reason| MarkdownProcessError::InvalidBibliography { path, reason })
 }
@@ -1,11 +1,15 @@
-mod control;
-mod full_service;
-mod sub_parser;
+pub mod control;
+pub mod full_service;
+pub mod sub_parser;
 
-use super::frontmatter::parse_toml_to_metadata;
+use super::frontmatter::{FrontmatterSchema, find_unknown_keys, parse_toml_to_metadata};
 use full_service::MarkdownParser;
-use loss72_platemaker_core::model::Article;
+use loss72_platemaker_core::{
+    log,
+    model::{Article, ArticleMetadata, DocumentOutline},
+};
 use loss72_platemaker_structure::ArticleFile;
+use sub_parser::{SubParserOutputs, SubParsers};
 
 pub type ParseResult<T> = Result<T, ParseError>;
 
@@ -26,27 +30,90 @@ pub enum ParseError {
 
     #[error("The frontmatter could not be parsed or not valid metadata:\n{0}")]
     InvalidToml(String),
+
+    #[error(
+        "The frontmatter has problems:\n{}",
+        .0.iter().map(|problem| format!("  - {problem}")).collect::<Vec<_>>().join("\n")
+    )]
+    InvalidFrontmatter(Vec<String>),
 }
 
-pub fn make_article_from_markdown(file: &ArticleFile, content: &str) -> ParseResult<Article> {
-    let content = parse_markdown(content)?;
-    let metadata = parse_toml_to_metadata(&content.frontmatter)?;
+pub fn make_article_from_markdown_with<'p>(
+    file: &ArticleFile,
+    content: &'p str,
+    sub_parsers: SubParsers<'p>,
+    schema: &FrontmatterSchema,
+) -> ParseResult<(Article, SubParserOutputs)> {
+    let content = parse_markdown(content, sub_parsers)?;
+    let metadata = parse_frontmatter(&content.frontmatter, schema)?;
 
-    Ok(Article {
-        id: file.id.clone(),
-        metadata,
-        content: content.html,
-    })
+    Ok((
+        Article {
+            id: file.id.clone(),
+            metadata,
+            content: content.html,
+            outline: content.outline,
+            history: None,
+        },
+        content.outputs,
+    ))
+}
+
+/// Unknown keys are rejected in the strict mode. Otherwise they are warned, and the undeclared widgets and article
+/// types are dropped.
+fn parse_frontmatter(
+    frontmatter: &str,
+    schema: &FrontmatterSchema,
+) -> ParseResult<ArticleMetadata> {
+    let unknown_keys = find_unknown_keys(frontmatter, schema);
+    let metadata = parse_toml_to_metadata(frontmatter);
+
+    if schema.strict && !unknown_keys.is_empty() {
+        // Reports the other problems together, so that they can be fixed at once
+        let mut problems = unknown_keys;
+        if let Err(ParseError::InvalidToml(error)) = metadata {
+            problems.push(error);
+        }
+
+        return Err(ParseError::InvalidFrontmatter(problems));
+    }
+
+    for unknown_key in unknown_keys {
+        log!(warn: "{}, which is ignored", unknown_key);
+    }
+
+    let mut metadata = metadata?;
+    // Undeclared widgets and article types are warned above as ignored, so they should not reach the rendering
+    metadata
+        .widgets
+        .user
+        .retain(|name, _| schema.user_widgets.contains(name));
+    if !schema.article_types.is_empty()
+        && metadata
+            .widgets
+            .article_type
+            .as_ref()
+            .is_some_and(|article_type| !schema.article_types.contains(&article_type.0))
+    {
+        // Falls back to the default type
+        metadata.widgets.article_type = None;
+    }
+
+    Ok(metadata)
 }
 
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 struct ParsedContent {
     frontmatter: String,
     html: String,
+    outline: DocumentOutline,
+    outputs: SubParserOutputs,
 }
 
-fn parse_markdown(content: &str) -> ParseResult<ParsedContent> {
-    let parsed = MarkdownParser::parse(content, pulldown_cmark::Options::all());
+fn parse_markdown<'p>(content: &'p str, sub_parsers: SubParsers<'p>) -> ParseResult<ParsedContent> {
+    let parsed =
+        MarkdownParser::with_sub_parsers(content, pulldown_cmark::Options::all(), sub_parsers)
+            .run();
 
     Ok(ParsedContent {
         html: parsed.html().to_string(),
@@ -54,5 +121,51 @@ fn parse_markdown(content: &str) -> ParseResult<ParsedContent> {
             .frontmatter()
             .ok_or(ParseError::NoFrontmatter)?
             .to_string(),
+        outline: parsed.outline,
+        outputs: parsed.outputs,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::frontmatter::FrontmatterSchema;
+
+    use super::parse_frontmatter;
+
+    const FRONTMATTER: &str = r#"
+        title = "Title"
+        brief = "Brief"
+
+        [widgets]
+        aii = "Review"
+        article_type = "Reserch"
+        calout = { title = "Note" }
+        callout = { title = "Note" }
+    "#;
+
+    fn schema(strict: bool) -> FrontmatterSchema {
+        FrontmatterSchema {
+            user_widgets: vec!["callout".to_string()],
+            article_types: vec!["Activity".to_string(), "Research".to_string()],
+            strict,
+        }
+    }
+
+    #[test]
+    fn drops_unknown_widgets_in_non_strict_mode() {
+        let metadata =
+            parse_frontmatter(FRONTMATTER, &schema(false)).expect("unknown keys should be ignored");
+
+        assert_eq!(metadata.title, "Title");
+        assert_eq!(
+            metadata.widgets.user.keys().collect::<Vec<_>>(),
+            ["callout"]
+        );
+        assert!(metadata.widgets.article_type.is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_widgets_in_strict_mode() {
+        assert!(parse_frontmatter(FRONTMATTER, &schema(true)).is_err());
+    }
+}
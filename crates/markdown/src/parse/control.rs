@@ -9,13 +9,11 @@ pub struct Next<'p> {
 }
 
 impl<'p> Next<'p> {
+    /// Merges the request of the sub parser registered later. The later replacement wins since the sub parser
+    /// has received the earlier one, while the earlier request to ignore wins and the later one is dropped.
     pub(super) fn update_by(&mut self, other: Next<'p>) {
-        if other.ignore.is_some() && self.ignore.is_some() {
-            panic!("Multiple sub parser requested to ignore");
-        }
-
         *self = Self {
-            ignore: other.ignore,
+            ignore: self.ignore.take().or(other.ignore),
             replacement: other.replacement.or(self.replacement.take()),
         }
     }
@@ -98,3 +96,28 @@ pub fn use_next<'p>() -> EventProcessControl<'p> {
 pub fn use_next_with(next: Next) -> EventProcessControl {
     ControlFlow::Continue(next)
 }
+
+#[cfg(test)]
+mod tests {
+    use pulldown_cmark::Event;
+
+    use super::{Ignore, Next};
+
+    #[test]
+    fn keeps_the_earlier_ignore_request() {
+        let mut next = Next {
+            replacement: Some(Event::SoftBreak),
+            ignore: Some(Ignore::ForNext(1)),
+        };
+        next.update_by(Next {
+            replacement: Some(Event::HardBreak),
+            ignore: Some(Ignore::ForNext(2)),
+        });
+        assert!(matches!(next.ignore, Some(Ignore::ForNext(1))));
+        assert_eq!(next.replacement, Some(Event::HardBreak));
+
+        next.update_by(Next::default());
+        assert!(matches!(next.ignore, Some(Ignore::ForNext(1))));
+        assert_eq!(next.replacement, Some(Event::HardBreak));
+    }
+}
@@ -1,16 +1,28 @@
 use std::{collections::VecDeque, ops::ControlFlow};
 
-use pulldown_cmark::{Event, Options, Parser};
+use loss72_platemaker_core::model::DocumentOutline;
+use pulldown_cmark::{BrokenLink, CowStr, Event, Options, Parser};
 
 use super::{
     control::{BreakingEventProcess, Ignore},
-    sub_parser::{SubParser, SubParsers},
+    sub_parser::{SubParserOutputs, SubParsers, citation_keys},
 };
 
+type BrokenLinkCallback<'p> = fn(BrokenLink<'p>) -> Option<(CowStr<'p>, CowStr<'p>)>;
+
+/// Keeps `[@key]` as a link to `@key` for [`super::sub_parser::CitationSubParser`], instead of the plain text.
+fn citation_link<'p>(link: BrokenLink<'p>) -> Option<(CowStr<'p>, CowStr<'p>)> {
+    citation_keys(&link.reference)?;
+
+    Some((link.reference, CowStr::Borrowed("")))
+}
+
 #[derive(Default, Debug)]
 pub struct MarkdownParseResult {
     pub frontmatter: Option<String>,
     pub html: String,
+    pub outline: DocumentOutline,
+    pub outputs: SubParserOutputs,
 }
 
 impl MarkdownParseResult {
@@ -21,10 +33,14 @@ impl MarkdownParseResult {
     pub fn frontmatter(&self) -> Option<&str> {
         self.frontmatter.as_deref()
     }
+
+    pub fn outline(&self) -> &DocumentOutline {
+        &self.outline
+    }
 }
 
 pub struct MarkdownParser<'p> {
-    parser: Parser<'p>,
+    parser: Parser<'p, BrokenLinkCallback<'p>>,
     sub_parser: SubParsers<'p>,
     ignore: Option<Ignore<'p>>,
     finalized: bool,
@@ -33,9 +49,21 @@ pub struct MarkdownParser<'p> {
 
 impl<'p> MarkdownParser<'p> {
     pub fn new(content: &'p str, parser_option: Options) -> Self {
+        Self::with_sub_parsers(content, parser_option, SubParsers::default())
+    }
+
+    pub fn with_sub_parsers(
+        content: &'p str,
+        parser_option: Options,
+        sub_parser: SubParsers<'p>,
+    ) -> Self {
         MarkdownParser {
-            sub_parser: SubParsers::default(),
-            parser: pulldown_cmark::Parser::new_ext(content, parser_option),
+            sub_parser,
+            parser: pulldown_cmark::Parser::new_with_broken_link_callback(
+                content,
+                parser_option,
+                Some(citation_link as BrokenLinkCallback<'p>),
+            ),
             ignore: None,
             finalized: false,
             last_append: VecDeque::new(),
@@ -46,9 +74,17 @@ impl<'p> MarkdownParser<'p> {
         let mut html = String::new();
         pulldown_cmark::html::push_html(&mut html, self.flatten());
 
+        let frontmatter = self.sub_parser.frontmatter();
+        let outline = self.sub_parser.outline();
+        let mut outputs = self.sub_parser.compose_outputs();
+
         MarkdownParseResult {
-            frontmatter: self.sub_parser.frontmatter.compose_output().body,
+            frontmatter: outputs
+                .take(frontmatter)
+                .and_then(|frontmatter| frontmatter.body),
             html,
+            outline: outputs.take(outline).unwrap_or_default(),
+            outputs,
         }
     }
 
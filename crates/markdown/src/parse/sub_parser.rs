@@ -1,12 +1,22 @@
+use std::{any::Any, marker::PhantomData};
+
 use pulldown_cmark::Event;
 
 use super::control::{EventProcessControl, Next};
 
+mod citation;
 mod code_block;
 mod footnote;
 mod frontmatter;
+mod outline;
 mod text;
 
+pub(crate) use citation::citation_keys;
+pub use citation::{CitationSubParser, Citations};
+pub use frontmatter::Frontmatter;
+use loss72_platemaker_core::model::DocumentOutline;
+use loss72_platemaker_widgets::messages::{MessageCatalog, Messages};
+
 pub trait SubParser<'p> {
     type Output;
 
@@ -17,30 +27,285 @@ pub trait SubParser<'p> {
     fn compose_output(self) -> Self::Output;
 }
 
-#[derive(Default)]
+/// Object-safe form of [`SubParser`] so that parsers with different outputs can live in one list.
+trait DynSubParser<'p> {
+    fn receive_event(&mut self, event: &Event<'p>) -> EventProcessControl<'p>;
+    fn finalize(&mut self) -> Option<Vec<Event<'p>>>;
+    fn compose_output(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<'p, T> DynSubParser<'p> for T
+where
+    T: SubParser<'p>,
+    T::Output: 'static,
+{
+    fn receive_event(&mut self, event: &Event<'p>) -> EventProcessControl<'p> {
+        SubParser::receive_event(self, event)
+    }
+
+    fn finalize(&mut self) -> Option<Vec<Event<'p>>> {
+        SubParser::finalize(self)
+    }
+
+    fn compose_output(self: Box<Self>) -> Box<dyn Any> {
+        Box::new(SubParser::compose_output(*self))
+    }
+}
+
+/// Refers the output of a sub parser registered to [`SubParsers`].
+#[derive(Debug)]
+pub struct SubParserHandle<O> {
+    slot: usize,
+    _output: PhantomData<fn() -> O>,
+}
+
+impl<O> Clone for SubParserHandle<O> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<O> Copy for SubParserHandle<O> {}
+
+struct RegisteredSubParser<'p> {
+    slot: usize,
+    parser: Box<dyn DynSubParser<'p> + 'p>,
+}
+
+/// Ordered list of sub parsers. Each event is passed to the sub parsers from the first one.
+///
+/// The default set consists of the built-in sub parsers, in this order:
+/// outline, code block, footnote, frontmatter and text (emoji).
+/// [`SubParsers::with_messages`] makes the set showing the messages in another locale.
 pub struct SubParsers<'p> {
-    pub code_block: code_block::CodeBlockSubParser,
-    pub footnote: footnote::FootnoteSubParser<'p>,
-    pub frontmatter: frontmatter::FrontmatterSubParser,
-    pub text: text::TextParser,
+    parsers: Vec<RegisteredSubParser<'p>>,
+    next_slot: usize,
+    frontmatter: Option<SubParserHandle<Frontmatter>>,
+    outline: Option<SubParserHandle<DocumentOutline>>,
+}
+
+impl Default for SubParsers<'_> {
+    fn default() -> Self {
+        Self::with_messages(&MessageCatalog::default().messages(None))
+    }
 }
 
 impl<'p> SubParsers<'p> {
-    pub fn receive_event(&mut self, event: &Event<'p>) -> EventProcessControl<'p> {
+    /// The default set of the sub parsers, using `messages` for the texts they add.
+    pub fn with_messages(messages: &Messages) -> Self {
+        let mut sub_parsers = Self {
+            parsers: vec![],
+            next_slot: 0,
+            frontmatter: None,
+            outline: None,
+        };
+
+        sub_parsers.outline = Some(sub_parsers.push(outline::OutlineSubParser::default()));
+        sub_parsers.push(code_block::CodeBlockSubParser::default());
+        sub_parsers.push(footnote::FootnoteSubParser::new(messages));
+        sub_parsers.frontmatter =
+            Some(sub_parsers.push(frontmatter::FrontmatterSubParser::default()));
+        sub_parsers.push(text::TextParser::default());
+
+        sub_parsers
+    }
+
+    /// Number of the registered sub parsers, including the built-in ones.
+    pub fn len(&self) -> usize {
+        self.parsers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parsers.is_empty()
+    }
+
+    /// Registers the sub parser at the end, so that it receives events after all the others.
+    pub fn push<P>(&mut self, parser: P) -> SubParserHandle<P::Output>
+    where
+        P: SubParser<'p> + 'p,
+        P::Output: 'static,
+    {
+        self.insert(self.parsers.len(), parser)
+    }
+
+    /// Registers the sub parser at `index`, so that it receives events before the sub parsers after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert<P>(&mut self, index: usize, parser: P) -> SubParserHandle<P::Output>
+    where
+        P: SubParser<'p> + 'p,
+        P::Output: 'static,
+    {
+        let slot = self.next_slot;
+        self.next_slot += 1;
+
+        self.parsers.insert(
+            index,
+            RegisteredSubParser {
+                slot,
+                parser: Box::new(parser),
+            },
+        );
+
+        SubParserHandle {
+            slot,
+            _output: PhantomData,
+        }
+    }
+
+    pub(crate) fn frontmatter(&self) -> SubParserHandle<Frontmatter> {
+        self.frontmatter
+            .expect("frontmatter sub parser to be registered by default")
+    }
+
+    pub(crate) fn outline(&self) -> SubParserHandle<DocumentOutline> {
+        self.outline
+            .expect("outline sub parser to be registered by default")
+    }
+
+    pub(crate) fn receive_event(&mut self, event: &Event<'p>) -> EventProcessControl<'p> {
         let mut next = Next::default();
-        next.update_by(self.code_block.receive_event(next.next_event(event))?);
-        next.update_by(self.footnote.receive_event(next.next_event(event))?);
-        next.update_by(self.frontmatter.receive_event(next.next_event(event))?);
-        next.update_by(self.text.receive_event(next.next_event(event))?);
+
+        for registered in &mut self.parsers {
+            next.update_by(registered.parser.receive_event(next.next_event(event))?);
+        }
 
         EventProcessControl::Continue(next)
     }
 
-    pub fn finalize(&mut self) -> Vec<Event<'p>> {
+    pub(crate) fn finalize(&mut self) -> Vec<Event<'p>> {
         let mut vec = vec![];
 
-        vec.append(&mut self.footnote.finalize().unwrap_or(Vec::new()));
+        for registered in &mut self.parsers {
+            vec.append(&mut registered.parser.finalize().unwrap_or_default());
+        }
 
         vec
     }
+
+    pub(crate) fn compose_outputs(self) -> SubParserOutputs {
+        let mut outputs = SubParserOutputs::default();
+
+        for registered in self.parsers {
+            outputs.insert(registered.slot, registered.parser.compose_output());
+        }
+
+        outputs
+    }
+}
+
+/// Outputs composed by each sub parser after the whole document is parsed.
+#[derive(Default)]
+pub struct SubParserOutputs {
+    outputs: Vec<Option<Box<dyn Any>>>,
+}
+
+impl SubParserOutputs {
+    fn insert(&mut self, slot: usize, output: Box<dyn Any>) {
+        if self.outputs.len() <= slot {
+            self.outputs.resize_with(slot + 1, || None);
+        }
+
+        self.outputs[slot] = Some(output);
+    }
+
+    /// Takes the output of the sub parser referred by `handle`. Returns `None` if it is already taken.
+    pub fn take<O: 'static>(&mut self, handle: SubParserHandle<O>) -> Option<O> {
+        let output = self.outputs.get_mut(handle.slot)?.take()?;
+
+        Some(
+            *output
+                .downcast::<O>()
+                .expect("handle to be issued for the sub parser with the same output type"),
+        )
+    }
+}
+
+impl std::fmt::Debug for SubParserOutputs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubParserOutputs")
+            .field(
+                "remaining",
+                &self
+                    .outputs
+                    .iter()
+                    .filter(|output| output.is_some())
+                    .count(),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pulldown_cmark::{Event, Options, Tag};
+
+    use super::{SubParser, SubParsers};
+    use crate::{
+        MarkdownParser,
+        control::{EventProcessControl, use_html, use_next},
+    };
+
+    #[derive(Default)]
+    struct HeadingCounter {
+        count: usize,
+    }
+
+    impl<'p> SubParser<'p> for HeadingCounter {
+        type Output = usize;
+
+        fn receive_event(&mut self, event: &Event<'p>) -> EventProcessControl<'p> {
+            if let Event::Start(Tag::Heading { .. }) = event {
+                self.count += 1;
+            }
+            use_next()
+        }
+
+        fn finalize(&mut self) -> Option<Vec<Event<'p>>> {
+            Some(vec![Event::Html(
+                format!("<p>{} headings</p>", self.count).into(),
+            )])
+        }
+
+        fn compose_output(self) -> Self::Output {
+            self.count
+        }
+    }
+
+    struct Shout;
+
+    impl<'p> SubParser<'p> for Shout {
+        type Output = ();
+
+        fn receive_event(&mut self, event: &Event<'p>) -> EventProcessControl<'p> {
+            match event {
+                Event::Text(text) if text.as_ref() == "hello" => use_html("HELLO".into()),
+                _ => use_next(),
+            }
+        }
+
+        fn compose_output(self) -> Self::Output {}
+    }
+
+    #[test]
+    fn registered_sub_parsers_receive_events_and_compose_output() {
+        let mut sub_parsers = SubParsers::default();
+        let counter = sub_parsers.push(HeadingCounter::default());
+        sub_parsers.insert(0, Shout);
+
+        let mut parsed = MarkdownParser::with_sub_parsers(
+            "+++\ntitle = \"a\"\n+++\n\n# A\n\nhello\n\n## B\n",
+            Options::all(),
+            sub_parsers,
+        )
+        .run();
+
+        assert_eq!(parsed.frontmatter(), Some("title = \"a\"\n"));
+        assert!(parsed.html().contains("<p>HELLO</p>"));
+        assert!(parsed.html().ends_with("<p>2 headings</p>"));
+        assert_eq!(parsed.outputs.take(counter), Some(2));
+        assert_eq!(parsed.outputs.take(counter), None);
+    }
 }
@@ -0,0 +1,138 @@
+use pulldown_cmark::{CowStr, Event, LinkType, Tag, TagEnd};
+
+use crate::parse::control::{EventProcessControl, discard, use_html, use_next};
+
+use super::SubParser;
+
+/// Keys in `[@key]` or `[@first; @second]`, or `None` if the reference is not a citation.
+pub(crate) fn citation_keys(reference: &str) -> Option<Vec<&str>> {
+    reference
+        .split(';')
+        .map(|key| {
+            key.trim()
+                .strip_prefix('@')
+                .filter(|key| !key.is_empty() && !key.contains(char::is_whitespace))
+        })
+        .collect()
+}
+
+/// Keys cited in the body, and the ones not found in the sources.
+#[derive(Debug, Default)]
+pub struct Citations {
+    /// In the order of the first citation.
+    pub cited: Vec<String>,
+    pub undefined: Vec<String>,
+}
+
+/// Replaces the citations with the numbered links to the sources, numbered by the position in the sources.
+///
+/// The citations come as the links to the unknown references, made by the parser for `[@key]`.
+pub struct CitationSubParser {
+    /// Keys of every source in the order of the list, `None` for the sources without keys.
+    source_keys: Vec<Option<String>>,
+    citing: Option<Vec<String>>,
+    citations: Citations,
+}
+
+impl CitationSubParser {
+    pub fn new(source_keys: Vec<Option<String>>) -> Self {
+        Self {
+            source_keys,
+            citing: None,
+            citations: Citations::default(),
+        }
+    }
+
+    fn render(&mut self, keys: Vec<String>) -> String {
+        let links = keys
+            .into_iter()
+            .map(|key| {
+                let number = self
+                    .source_keys
+                    .iter()
+                    .position(|source| source.as_ref() == Some(&key));
+
+                let link = match number {
+                    Some(index) => format!(r##"<a href="#source-{key}">{}</a>"##, index + 1),
+                    None => {
+                        if !self.citations.undefined.contains(&key) {
+                            self.citations.undefined.push(key.clone());
+                        }
+                        format!(r#"<span class="undefined" title="{key}">?</span>"#)
+                    }
+                };
+
+                if !self.citations.cited.contains(&key) {
+                    self.citations.cited.push(key);
+                }
+
+                link
+            })
+            .collect::<Vec<_>>();
+
+        format!(r#"<sup class="citation">[{}]</sup>"#, links.join(", "))
+    }
+}
+
+impl<'p> SubParser<'p> for CitationSubParser {
+    type Output = Citations;
+
+    fn receive_event(&mut self, event: &Event<'p>) -> EventProcessControl<'p> {
+        match event {
+            Event::Start(Tag::Link {
+                link_type: LinkType::ShortcutUnknown,
+                dest_url,
+                ..
+            }) if self.citing.is_none() => match citation_keys(dest_url) {
+                Some(keys) => {
+                    self.citing = Some(keys.into_iter().map(str::to_string).collect());
+                    discard()
+                }
+                None => use_next(),
+            },
+            Event::End(TagEnd::Link) if self.citing.is_some() => {
+                let keys = self.citing.take().unwrap_or_default();
+                use_html(CowStr::from(self.render(keys)))
+            }
+            _ if self.citing.is_some() => discard(),
+            _ => use_next(),
+        }
+    }
+
+    fn compose_output(self) -> Self::Output {
+        self.citations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CitationSubParser, citation_keys};
+
+    #[test]
+    fn reads_citation_keys() {
+        assert_eq!(citation_keys("@rust"), Some(vec!["rust"]));
+        assert_eq!(citation_keys("@rust; @docs"), Some(vec!["rust", "docs"]));
+        assert_eq!(citation_keys("rust"), None);
+        assert_eq!(citation_keys("@rust; docs"), None);
+        assert_eq!(citation_keys("@"), None);
+    }
+
+    #[test]
+    fn numbers_citations_by_position_in_all_sources() {
+        let mut parser = CitationSubParser::new(vec![
+            None,
+            Some("rust".to_string()),
+            Some("docs".to_string()),
+        ]);
+
+        assert_eq!(
+            parser.render(vec![
+                "docs".to_string(),
+                "rust".to_string(),
+                "unknown".to_string()
+            ]),
+            r##"<sup class="citation">[<a href="#source-docs">3</a>, <a href="#source-rust">2</a>, <span class="undefined" title="unknown">?</span>]</sup>"##
+        );
+        assert_eq!(parser.citations.undefined, vec!["unknown"]);
+    }
+}
@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 
+use loss72_platemaker_widgets::messages::Messages;
 use pulldown_cmark::{Event, Tag, TagEnd};
 
 use crate::parse::control::{EventProcessControl, discard, use_html, use_next};
@@ -85,11 +86,25 @@ impl FootnoteSubParser<'_> {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct FootnoteSubParser<'p> {
     footnotes: Vec<FootNoteDefinition<'p>>,
     footnote_refs: Vec<FootNoteRef>,
     building_footnotes: Option<FootNoteDefinition<'p>>,
+    title: String,
+    back: String,
+}
+
+impl FootnoteSubParser<'_> {
+    pub fn new(messages: &Messages) -> Self {
+        Self {
+            footnotes: vec![],
+            footnote_refs: vec![],
+            building_footnotes: None,
+            title: messages.get("footnote.title").to_string(),
+            back: messages.get("footnote.back").to_string(),
+        }
+    }
 }
 
 impl<'p> SubParser<'p> for FootnoteSubParser<'p> {
@@ -122,7 +137,7 @@ impl<'p> SubParser<'p> for FootnoteSubParser<'p> {
 
         let mut events = vec![];
         events.push(Event::Html(
-            r#"<aside class="footnote-def"><h1>脚注</h1><ol>"#.into(),
+            format!(r#"<aside class="footnote-def"><h1>{}</h1><ol>"#, self.title).into(),
         ));
 
         let mut footnotes = self
@@ -140,6 +155,7 @@ impl<'p> SubParser<'p> for FootnoteSubParser<'p> {
             .collect::<Vec<_>>();
         footnotes.sort();
 
+        let back = &self.back;
         for FootnoteSort { def, refer } in footnotes.iter_mut() {
             let id = &def.1.id;
 
@@ -150,7 +166,7 @@ impl<'p> SubParser<'p> for FootnoteSubParser<'p> {
                 events.extend(
                     (1..=refer.1.ref_count)
                         .map(|index| {
-                            format!(r##"<sub><a href="#fnref_{id}_{index}">戻る</a></sub>"##)
+                            format!(r##"<sub><a href="#fnref_{id}_{index}">{back}</a></sub>"##)
                         })
                         .map(|html| Event::Html(html.into())),
                 );
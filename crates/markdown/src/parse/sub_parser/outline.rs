@@ -0,0 +1,136 @@
+use loss72_platemaker_core::model::{DocumentOutline, OutlineHeading, OutlineImage};
+use pulldown_cmark::{CodeBlockKind, Event, Tag, TagEnd};
+
+use crate::parse::control::{EventProcessControl, use_next};
+
+use super::SubParser;
+
+enum CollectingText {
+    Heading(OutlineHeading),
+    Image(OutlineImage),
+}
+
+/// Collects the outline of the document. This never alters the events.
+///
+/// Footnote definitions are skipped when they first appear, since the footnote sub parser
+/// sends their content again on finalization.
+#[derive(Default)]
+pub struct OutlineSubParser {
+    outline: DocumentOutline,
+    collecting: Vec<CollectingText>,
+    footnote_depth: usize,
+}
+
+impl<'p> SubParser<'p> for OutlineSubParser {
+    type Output = DocumentOutline;
+
+    fn receive_event(&mut self, event: &Event<'p>) -> EventProcessControl<'p> {
+        match event {
+            Event::Start(Tag::FootnoteDefinition(_)) => {
+                self.outline.footnote_count += 1;
+                self.footnote_depth += 1;
+            }
+            Event::End(TagEnd::FootnoteDefinition) => {
+                self.footnote_depth = self.footnote_depth.saturating_sub(1);
+            }
+            _ if self.footnote_depth > 0 => {}
+            Event::Start(Tag::Heading { level, id, .. }) => {
+                self.collecting
+                    .push(CollectingText::Heading(OutlineHeading {
+                        level: *level as u8,
+                        text: String::new(),
+                        id: id.as_ref().map(|id| id.to_string()),
+                    }));
+            }
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                self.collecting.push(CollectingText::Image(OutlineImage {
+                    url: dest_url.to_string(),
+                    alt: String::new(),
+                }));
+            }
+            Event::End(TagEnd::Heading(_) | TagEnd::Image) => match self.collecting.pop() {
+                Some(CollectingText::Heading(heading)) => self.outline.headings.push(heading),
+                Some(CollectingText::Image(image)) => self.outline.images.push(image),
+                None => {}
+            },
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                self.outline.links.push(dest_url.to_string());
+            }
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang)))
+                if !lang.is_empty()
+                    && !self
+                        .outline
+                        .code_languages
+                        .iter()
+                        .any(|known| known == lang.as_ref()) =>
+            {
+                self.outline.code_languages.push(lang.to_string());
+            }
+            Event::Text(text) | Event::Code(text) => match self.collecting.last_mut() {
+                Some(CollectingText::Heading(heading)) => heading.text.push_str(text),
+                Some(CollectingText::Image(image)) => image.alt.push_str(text),
+                None => {}
+            },
+            _ => {}
+        }
+
+        use_next()
+    }
+
+    fn compose_output(self) -> Self::Output {
+        self.outline
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use loss72_platemaker_core::model::{OutlineHeading, OutlineImage};
+    use pulldown_cmark::Options;
+
+    use crate::MarkdownParser;
+
+    #[test]
+    fn collects_outline() {
+        let parsed = MarkdownParser::parse(
+            "+++\ntitle = \"a\"\n+++\n\n\
+             # Hello `world` {#hello}\n\n\
+             ![a cat](./assets/cat.png)\n\n\
+             [site](https://example.com) and [home](/index.html)[^1]\n\n\
+             ```rust\nfn main() {}\n```\n\n\
+             ```rust\nfn main() {}\n```\n\n\
+             [^1]: See [docs](https://docs.example.com)\n",
+            Options::all(),
+        );
+        let outline = parsed.outline();
+
+        assert_eq!(
+            outline.headings,
+            [OutlineHeading {
+                level: 1,
+                text: "Hello world".to_string(),
+                id: Some("hello".to_string()),
+            }]
+        );
+        assert_eq!(
+            outline.images,
+            [OutlineImage {
+                url: "./assets/cat.png".to_string(),
+                alt: "a cat".to_string(),
+            }]
+        );
+        assert_eq!(
+            outline.links,
+            [
+                "https://example.com",
+                "/index.html",
+                "https://docs.example.com"
+            ]
+        );
+        assert_eq!(
+            outline.outbound_links().collect::<Vec<_>>(),
+            ["https://example.com", "https://docs.example.com"]
+        );
+        assert_eq!(outline.code_languages, ["rust"]);
+        assert_eq!(outline.footnote_count, 1);
+    }
+}
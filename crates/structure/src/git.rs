@@ -0,0 +1,159 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use chrono::DateTime;
+use loss72_platemaker_core::{
+    fs::{Directory, File},
+    model::FileHistory,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum GitHistoryError {
+    #[error("Failed to run git: {0}")]
+    Spawn(#[from] std::io::Error),
+
+    #[error("git exited with failure:\n{0}")]
+    Git(String),
+
+    #[error("Unexpected output from git: {0}")]
+    InvalidOutput(String),
+}
+
+/// Commit history of the files, read from the local git repository.
+#[derive(Debug, Default)]
+pub struct GitHistory {
+    files: HashMap<PathBuf, FileHistory>,
+}
+
+impl GitHistory {
+    /// Reads the history of every file under `dir` from the repository containing it.
+    pub fn load(dir: &Directory) -> Result<Self, GitHistoryError> {
+        let toplevel = run_git(dir.path(), &["rev-parse", "--show-toplevel"])?;
+        let toplevel = Path::new(toplevel.trim()).canonicalize()?;
+
+        let log = run_git(
+            dir.path(),
+            &[
+                "-c",
+                "core.quotePath=off",
+                "log",
+                "--format=%x1e%h%x09%cI",
+                "--name-only",
+                "--",
+                ".",
+            ],
+        )?;
+
+        Ok(Self {
+            files: parse_log(&toplevel, &log)?,
+        })
+    }
+
+    /// Returns `None` if the file has never been committed.
+    pub fn get(&self, file: &File) -> Option<&FileHistory> {
+        self.files.get(file.path())
+    }
+}
+
+/// Parses the output of `git log --format=%x1e%h%x09%cI --name-only`, whose paths are relative to `toplevel`.
+fn parse_log(toplevel: &Path, log: &str) -> Result<HashMap<PathBuf, FileHistory>, GitHistoryError> {
+    let mut files = HashMap::<PathBuf, FileHistory>::new();
+
+    // Commits are listed from the newest one
+    for record in log.split('\x1e').filter(|record| !record.trim().is_empty()) {
+        let mut lines = record.lines();
+
+        let header = lines.next().unwrap_or_default();
+        let Some((commit, date)) = header.split_once('\t') else {
+            return Err(GitHistoryError::InvalidOutput(header.to_string()));
+        };
+        let date = DateTime::parse_from_rfc3339(date)
+            .map_err(|_| GitHistoryError::InvalidOutput(header.to_string()))?;
+
+        for path in lines.filter(|line| !line.is_empty()) {
+            files
+                .entry(toplevel.join(path))
+                .and_modify(|history| history.created = date)
+                .or_insert_with(|| FileHistory {
+                    created: date,
+                    updated: date,
+                    commit: commit.to_string(),
+                    path: path.to_string(),
+                });
+        }
+    }
+
+    Ok(files)
+}
+
+fn run_git(dir: &Path, args: &[&str]) -> Result<String, GitHistoryError> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+
+    if !output.status.success() {
+        return Err(GitHistoryError::Git(
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+
+    String::from_utf8(output.stdout).map_err(|e| GitHistoryError::InvalidOutput(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use chrono::DateTime;
+
+    use super::parse_log;
+
+    const LOG: &str = "\x1ec3c3c3c\t2025-03-20T12:00:00+09:00\n\
+        \n\
+        md/2025/3/14_renamed.md\n\
+        md/2025/3/12_hello.md\n\
+        \x1eb2b2b2b\t2025-03-16T12:00:00+09:00\n\
+        \x1ea1a1a1a\t2025-03-12T12:00:00+09:00\n\
+        \n\
+        md/2025/3/12_hello.md\n\
+        md/2025/3/14_second.md\n";
+
+    #[test]
+    fn parses_log() {
+        let files = parse_log(Path::new("/repo"), LOG).expect("log should be parsed");
+        let date = |date| DateTime::parse_from_rfc3339(date).expect("date should be valid");
+
+        let hello = &files[&PathBuf::from("/repo/md/2025/3/12_hello.md")];
+        assert_eq!(hello.created, date("2025-03-12T12:00:00+09:00"));
+        assert_eq!(hello.updated, date("2025-03-20T12:00:00+09:00"));
+        assert_eq!(hello.commit, "c3c3c3c");
+        assert_eq!(hello.path, "md/2025/3/12_hello.md");
+
+        // The renamed file only has the history after the rename, as the old path is a different file
+        let renamed = &files[&PathBuf::from("/repo/md/2025/3/14_renamed.md")];
+        assert_eq!(renamed.created, date("2025-03-20T12:00:00+09:00"));
+        assert_eq!(renamed.updated, date("2025-03-20T12:00:00+09:00"));
+
+        let second = &files[&PathBuf::from("/repo/md/2025/3/14_second.md")];
+        assert_eq!(second.commit, "a1a1a1a");
+
+        // The merge commit without files does not add anything
+        assert_eq!(files.len(), 3);
+    }
+
+    #[test]
+    fn parses_empty_log() {
+        assert!(
+            parse_log(Path::new("/repo"), "")
+                .expect("log should be parsed")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_log() {
+        assert!(parse_log(Path::new("/repo"), "\x1ea1a1a1a 2025-03-12\n\nfoo.md\n").is_err());
+        assert!(parse_log(Path::new("/repo"), "\x1ea1a1a1a\tyesterday\n\nfoo.md\n").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod git;
 pub mod template;
 
 use std::{
@@ -5,11 +6,37 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use chrono::NaiveDate;
 use loss72_platemaker_core::{
     fs::{Directory, FSNode, File},
     model::ArticleIdentifier,
 };
 
+#[derive(Debug, thiserror::Error)]
+pub enum ContentDirectoryError {
+    #[error("I/O Error: {0}")]
+    IOError(#[from] std::io::Error),
+
+    #[error(
+        "These paths do not represent valid dates:\n{}",
+        .0.iter().map(|path| format!("  - {path}")).collect::<Vec<_>>().join("\n")
+    )]
+    InvalidDates(Vec<InvalidDatePath>),
+
+    #[error(
+        "These files are the same article in the default language:\n{}",
+        .0.iter().map(|path| format!("  - ./{}", path.display())).collect::<Vec<_>>().join("\n")
+    )]
+    DuplicateArticles(Vec<PathBuf>),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("./{} ({reason})", .path.display())]
+pub struct InvalidDatePath {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
 pub struct ContentDirectory<'dir> {
     pub dir: &'dir Directory,
     pub markdown_files: Vec<ArticleFile>,
@@ -17,12 +44,28 @@ pub struct ContentDirectory<'dir> {
 }
 
 impl<'dir> ContentDirectory<'dir> {
-    pub fn new(dir: &'dir Directory) -> Result<Self, std::io::Error> {
-        let mut article_group = ArticleGroup::scan(dir)?;
+    /// Discovers the articles. Paths looking like articles or groups but with invalid dates
+    /// (e.g. `./2025/13/`, `./2025/x3/`, `./2025/2/30_foo.md` or `./2025/2/1x_foo.md`) are reported all at once.
+    ///
+    /// Articles suffixed with `default_language` are the same as the ones without a language, so only one of
+    /// `12_foo.md` and `12_foo.ja.md` can exist if it is `ja`.
+    pub fn new(
+        dir: &'dir Directory,
+        default_language: &str,
+    ) -> Result<Self, ContentDirectoryError> {
+        let mut article_group = vec![];
+        let mut invalid_dates = vec![];
+        for group in ArticleGroup::scan(dir)? {
+            match group {
+                Ok(group) => article_group.push(group),
+                Err(invalid) => invalid_dates.push(invalid),
+            }
+        }
         article_group.sort();
         article_group.dedup();
 
-        let markdown_files = article_group
+        let mut markdown_files = vec![];
+        let files = article_group
             .iter()
             .map(|group| {
                 Directory::new(dir.path().join(group.group_dir_path()))
@@ -31,10 +74,36 @@ impl<'dir> ContentDirectory<'dir> {
             .collect::<Result<Vec<_>, _>>()?
             .into_iter()
             .flatten()
-            .filter_map(|node| node.into_file())
-            .filter_map(|file| ArticleFile::from_file(&file, dir))
+            .filter_map(|node| node.into_file());
+
+        for file in files {
+            match ArticleFile::try_from_file(&file, dir, default_language) {
+                Ok(Some(article_file)) => markdown_files.push(article_file),
+                Ok(None) => {}
+                Err(invalid) => invalid_dates.push(invalid),
+            }
+        }
+
+        if !invalid_dates.is_empty() {
+            return Err(ContentDirectoryError::InvalidDates(invalid_dates));
+        }
+
+        let duplicates = markdown_files
+            .iter()
+            .filter(|file| {
+                markdown_files
+                    .iter()
+                    .filter(|other| other.id == file.id)
+                    .count()
+                    > 1
+            })
+            .map(|file| file.relative_path.clone())
             .collect::<Vec<_>>();
 
+        if !duplicates.is_empty() {
+            return Err(ContentDirectoryError::DuplicateArticles(duplicates));
+        }
+
         Ok(Self {
             dir,
             markdown_files,
@@ -50,18 +119,51 @@ pub struct ArticleGroup {
 }
 
 impl ArticleGroup {
-    pub fn scan(root: &Directory) -> std::io::Result<Vec<ArticleGroup>> {
+    /// Finds the group directories such as `./2025/3/`. The ones under a year without a valid month,
+    /// such as `./2025/13/` or `./2025/x3/`, are returned as the errors.
+    pub fn scan(root: &Directory) -> std::io::Result<Vec<Result<ArticleGroup, InvalidDatePath>>> {
         Ok(root
             .try_iter_tree()?
             .collect::<Result<Vec<_>, _>>()?
             .into_iter()
             .filter_map(|node| node.into_directory())
-            .filter_map(|dir| Self::from_path(dir.path().strip_prefix(root.path()).unwrap()))
-            .filter(|(_, suffix)| suffix.is_empty())
-            .map(|(group, _)| group)
+            .filter_map(|dir| Self::from_group_dir(dir.path().strip_prefix(root.path()).unwrap()))
             .collect::<Vec<_>>())
     }
 
+    /// `None` if `path` is not `$year/$month`, in which `$year` is a number.
+    fn from_group_dir(path: &Path) -> Option<Result<Self, InvalidDatePath>> {
+        let components = path
+            .iter()
+            .map(|cmp| cmp.to_str())
+            .collect::<Option<Vec<_>>>()?;
+        let [year, month] = components.as_slice() else {
+            return None;
+        };
+        let year = year.parse::<u32>().ok()?;
+
+        let Ok(month) = month.parse::<u8>() else {
+            return Some(Err(InvalidDatePath {
+                path: path.to_path_buf(),
+                reason: format!("{month} is not a valid month"),
+            }));
+        };
+
+        let group = Self { year, month };
+        Some(group.validate().map(|()| group))
+    }
+
+    fn validate(&self) -> Result<(), InvalidDatePath> {
+        if (1..=12).contains(&self.month) && i32::try_from(self.year).is_ok() {
+            Ok(())
+        } else {
+            Err(InvalidDatePath {
+                path: self.group_dir_path(),
+                reason: format!("{}/{} is not a valid month", self.year, self.month),
+            })
+        }
+    }
+
     pub fn group_dir_path(&self) -> PathBuf {
         PathBuf::new()
             .join(self.year.to_string())
@@ -116,25 +218,57 @@ pub struct ArticleFile {
 }
 
 impl ArticleFile {
-    pub fn from_file(file: &File, root: &Directory) -> Option<Self> {
-        let file = ArticleGroupNode::from_node(file.clone().into(), root)?;
+    /// Returns `None` for files that are not articles, including the ones at the paths with invalid dates.
+    /// Use [`ArticleFile::try_from_file`] to tell them apart.
+    pub fn from_file(file: &File, root: &Directory, default_language: &str) -> Option<Self> {
+        Self::try_from_file(file, root, default_language)
+            .ok()
+            .flatten()
+    }
 
-        // matches to files in /path/to/root/[numeric]_*.md
-        let [first] = file.suffix_components.as_slice() else {
-            return None;
+    /// The article in `default_language` gets no language, the same as the one without the language suffix.
+    pub fn try_from_file(
+        file: &File,
+        root: &Directory,
+        default_language: &str,
+    ) -> Result<Option<Self>, InvalidDatePath> {
+        let Some(file) = ArticleGroupNode::from_node(file.clone().into(), root) else {
+            return Ok(None);
         };
 
-        let slug_and_ext = first.split(".").collect::<Vec<_>>();
-        let [slug, "md"] = slug_and_ext.as_slice() else {
-            return None;
+        // matches to files in /path/to/root/[numeric][-numeric]_*[.lang].md
+        let [first] = file.suffix_components.as_slice() else {
+            return Ok(None);
         };
 
-        let day_and_rest = slug.split("_").collect::<Vec<_>>();
-        let [day, ..] = day_and_rest.as_slice() else {
-            return None;
+        let Some(ArticleFileName {
+            stem: slug,
+            day,
+            seq,
+            lang,
+        }) = parse_article_file_name(first, default_language)
+        else {
+            return match invalid_day_part(first) {
+                Some(day) => Err(InvalidDatePath {
+                    path: file.relative_path.clone(),
+                    reason: format!("{day} is not a valid day"),
+                }),
+                None => Ok(None),
+            };
         };
 
-        let day = day.parse::<u8>().ok()?;
+        let Some(date) = i32::try_from(file.group.year)
+            .ok()
+            .and_then(|year| NaiveDate::from_ymd_opt(year, file.group.month.into(), day.into()))
+        else {
+            return Err(InvalidDatePath {
+                path: file.relative_path.clone(),
+                reason: format!(
+                    "{}/{}/{} is not a valid date",
+                    file.group.year, file.group.month, day
+                ),
+            });
+        };
 
         let id = ArticleIdentifier {
             group: file
@@ -143,10 +277,12 @@ impl ArticleFile {
                 .to_string_lossy()
                 .to_string(),
             slug: slug.to_string(),
-            date: (file.group.year, file.group.month, day),
+            date,
+            seq,
+            lang: lang.map(str::to_string),
         };
 
-        Some(Self { node: file, id })
+        Ok(Some(Self { node: file, id }))
     }
 
     pub fn file(&self) -> &File {
@@ -165,6 +301,72 @@ impl Deref for ArticleFile {
     }
 }
 
+#[derive(Debug, PartialEq, Eq)]
+struct ArticleFileName<'a> {
+    /// Used as the slug, excluding the language.
+    stem: &'a str,
+    day: u8,
+    seq: Option<u32>,
+    lang: Option<&'a str>,
+}
+
+/// Splits `$day[-$num]_$slug[.$lang].md` into the stem, the day, the sequence number and the language.
+/// The language is `None` if it is omitted or is `default_language`.
+fn parse_article_file_name<'a>(
+    file_name: &'a str,
+    default_language: &str,
+) -> Option<ArticleFileName<'a>> {
+    let stem = file_name.strip_suffix(".md")?;
+
+    let (stem, lang) = match stem.split_once(".") {
+        Some((stem, lang)) if lang == default_language => (stem, None),
+        Some((stem, lang)) if is_language_tag(lang) => (stem, Some(lang)),
+        Some(_) => return None,
+        None => (stem, None),
+    };
+
+    let (day_and_seq, _) = stem.split_once("_")?;
+
+    let (day, seq) = match day_and_seq.split_once("-") {
+        Some((day, seq)) => (day, Some(seq.parse::<u32>().ok()?)),
+        None => (day_and_seq, None),
+    };
+
+    Some(ArticleFileName {
+        stem,
+        day: day.parse::<u8>().ok()?,
+        seq,
+        lang,
+    })
+}
+
+/// `$day[-$num]` of the Markdown file which starts with a digit but does not have a valid day, such as `1x_foo.md`.
+fn invalid_day_part(file_name: &str) -> Option<&str> {
+    let (day_and_seq, _) = file_name.strip_suffix(".md")?.split_once("_")?;
+
+    let is_valid = match day_and_seq.split_once("-") {
+        Some((day, seq)) => day.parse::<u8>().is_ok() && seq.parse::<u32>().is_ok(),
+        None => day_and_seq.parse::<u8>().is_ok(),
+    };
+
+    (day_and_seq.starts_with(|char: char| char.is_ascii_digit()) && !is_valid)
+        .then_some(day_and_seq)
+}
+
+/// Accepts the language tags such as `ja`, `en` and `zh-Hant`, whose primary subtag is 2 or 3 letters.
+fn is_language_tag(lang: &str) -> bool {
+    let mut subtags = lang.split('-');
+
+    let is_primary = |primary: &str| {
+        (2..=3).contains(&primary.len()) && primary.chars().all(|char| char.is_ascii_alphabetic())
+    };
+
+    subtags.next().is_some_and(is_primary)
+        && subtags.all(|subtag| {
+            !subtag.is_empty() && subtag.chars().all(|char| char.is_ascii_alphanumeric())
+        })
+}
+
 #[derive(Debug)]
 pub struct AssetFile(ArticleGroupNode);
 
@@ -228,3 +430,101 @@ impl Deref for AssetRootDir {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{ArticleFileName, ArticleGroup, invalid_day_part, parse_article_file_name};
+
+    fn file_name<'a>(
+        stem: &'a str,
+        day: u8,
+        seq: Option<u32>,
+        lang: Option<&'a str>,
+    ) -> Option<ArticleFileName<'a>> {
+        Some(ArticleFileName {
+            stem,
+            day,
+            seq,
+            lang,
+        })
+    }
+
+    #[test]
+    fn parses_article_file_name() {
+        assert_eq!(
+            parse_article_file_name("12_foo.md", "en"),
+            file_name("12_foo", 12, None, None)
+        );
+        assert_eq!(
+            parse_article_file_name("12-2_foo.md", "en"),
+            file_name("12-2_foo", 12, Some(2), None)
+        );
+        assert_eq!(
+            parse_article_file_name("3_foo_bar.md", "en"),
+            file_name("3_foo_bar", 3, None, None)
+        );
+        assert_eq!(
+            parse_article_file_name("12_foo.ja.md", "en"),
+            file_name("12_foo", 12, None, Some("ja"))
+        );
+        assert_eq!(
+            parse_article_file_name("12-2_foo.zh-Hant.md", "en"),
+            file_name("12-2_foo", 12, Some(2), Some("zh-Hant"))
+        );
+    }
+
+    #[test]
+    fn rejects_non_article_file_name() {
+        assert_eq!(parse_article_file_name("12_foo.txt", "en"), None);
+        assert_eq!(parse_article_file_name("12_foo.ja.en.md", "en"), None);
+        assert_eq!(parse_article_file_name("12_foo..md", "en"), None);
+        assert_eq!(parse_article_file_name("foo.md", "en"), None);
+        assert_eq!(parse_article_file_name("x_foo.md", "en"), None);
+        assert_eq!(parse_article_file_name("12-x_foo.md", "en"), None);
+        assert_eq!(parse_article_file_name("12_v1.2-release.md", "en"), None);
+        assert_eq!(parse_article_file_name("12_foo.1.md", "en"), None);
+    }
+
+    #[test]
+    fn treats_default_language_as_no_language() {
+        assert_eq!(
+            parse_article_file_name("12_foo.ja.md", "ja"),
+            file_name("12_foo", 12, None, None)
+        );
+        assert_eq!(
+            parse_article_file_name("12_foo.en.md", "ja"),
+            file_name("12_foo", 12, None, Some("en"))
+        );
+    }
+
+    #[test]
+    fn reports_invalid_day() {
+        assert_eq!(invalid_day_part("1x_post.md"), Some("1x"));
+        assert_eq!(invalid_day_part("12-x_post.md"), Some("12-x"));
+        assert_eq!(invalid_day_part("300_post.md"), Some("300"));
+        assert_eq!(invalid_day_part("12_post.md"), None);
+        assert_eq!(invalid_day_part("12-2_post.md"), None);
+        assert_eq!(invalid_day_part("x_post.md"), None);
+        assert_eq!(invalid_day_part("1x_post.txt"), None);
+    }
+
+    #[test]
+    fn reads_group_dir() {
+        let group = |path: &str| ArticleGroup::from_group_dir(Path::new(path));
+
+        assert_eq!(
+            group("2025/3").map(Result::ok),
+            Some(Some(ArticleGroup {
+                year: 2025,
+                month: 3
+            }))
+        );
+        assert!(matches!(group("2025/13"), Some(Err(_))));
+        assert!(matches!(group("2025/x3"), Some(Err(_))));
+        assert!(group("2025").is_none());
+        assert!(group("assets/x3").is_none());
+        assert!(group("2025/3/assets").is_none());
+    }
+}
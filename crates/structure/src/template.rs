@@ -3,10 +3,40 @@ use std::path::{Path, PathBuf};
 pub const ARTICLE_TEMPLATE: &str = "_article.html";
 pub const INDEX_TEMPLATE: &str = "_index.html";
 pub const INDEX_LIST_TEMPLATE: &str = "_index-list.html";
+pub const TAG_TEMPLATE: &str = "_tag.html";
+pub const TAG_LIST_TEMPLATE: &str = "_tag-list.html";
+pub const TAGS_TEMPLATE: &str = "_tags.html";
+pub const TAGS_LIST_TEMPLATE: &str = "_tags-list.html";
+pub const SERIES_TEMPLATE: &str = "_series.html";
+pub const SERIES_LIST_TEMPLATE: &str = "_series-list.html";
+pub const AUTHOR_TEMPLATE: &str = "_author.html";
+pub const AUTHOR_LIST_TEMPLATE: &str = "_author-list.html";
+pub const AI_USAGE_TEMPLATE: &str = "_ai-usage.html";
+pub const AI_USAGE_LIST_TEMPLATE: &str = "_ai-usage-list.html";
+pub const TYPE_TEMPLATE: &str = "_type.html";
+pub const AUTHORS_FILE: &str = "authors.toml";
+pub const MESSAGES_FILE: &str = "messages.toml";
 
-pub const TEMPLATE_FILES: [&str; 3] = [ARTICLE_TEMPLATE, INDEX_TEMPLATE, INDEX_LIST_TEMPLATE];
+pub const TEMPLATE_FILES: [&str; 16] = [
+    ARTICLE_TEMPLATE,
+    INDEX_TEMPLATE,
+    INDEX_LIST_TEMPLATE,
+    TAG_TEMPLATE,
+    TAG_LIST_TEMPLATE,
+    TAGS_TEMPLATE,
+    TAGS_LIST_TEMPLATE,
+    SERIES_TEMPLATE,
+    SERIES_LIST_TEMPLATE,
+    AUTHOR_TEMPLATE,
+    AUTHOR_LIST_TEMPLATE,
+    AI_USAGE_TEMPLATE,
+    AI_USAGE_LIST_TEMPLATE,
+    TYPE_TEMPLATE,
+    AUTHORS_FILE,
+    MESSAGES_FILE,
+];
 
-pub fn template_file_paths() -> [PathBuf; 3] {
+pub fn template_file_paths() -> [PathBuf; 16] {
     TEMPLATE_FILES.map(PathBuf::from)
 }
 
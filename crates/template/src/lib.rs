@@ -4,6 +4,10 @@ use std::sync::LazyLock;
 
 use regex::{Captures, Regex};
 
+mod tree;
+
+pub use tree::{Context, Item, Template, TemplateError, TemplateLoader};
+
 pub const REGEX_CAPTURE_GROUP: &str = "name";
 
 #[derive(Debug)]
@@ -0,0 +1,907 @@
+use std::collections::HashMap;
+
+use crate::{Placeholder, REGEX_CAPTURE_GROUP};
+
+/// Fields of an item iterated by `${#each}`, looked up before the outer values inside the block.
+/// Lists of plain values give the value as `this`.
+pub type Item = HashMap<String, String>;
+
+/// Values given to a [`Template`] while rendering.
+pub trait Context {
+    /// Text for `${name}`, or `None` if the name is unknown.
+    fn text(&self, name: &str) -> Option<String>;
+
+    /// Items for `${#each name}`, or `None` if `name` is not a list.
+    fn items(&self, _name: &str) -> Option<Vec<Item>> {
+        None
+    }
+}
+
+impl Context for HashMap<&str, String> {
+    fn text(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum TemplateError {
+    #[error("`{tag}` at line {line} is never closed")]
+    Unclosed { tag: String, line: usize },
+
+    #[error("`{tag}` at line {line} does not close any block")]
+    UnexpectedClose { tag: String, line: usize },
+
+    #[error("`{tag}` at line {line} closes `{open}` opened at line {open_line}")]
+    MismatchedClose {
+        tag: String,
+        line: usize,
+        open: String,
+        open_line: usize,
+    },
+
+    #[error("`{tag}` at line {line} is not in `#if` without `#else` yet")]
+    MisplacedElse { tag: String, line: usize },
+
+    #[error("`{tag}` at line {line} is not a known block; use `#if`, `#else`, `#each` or `block`")]
+    UnknownBlock { tag: String, line: usize },
+
+    #[error("`{tag}` at line {line} needs a name to look up")]
+    MissingName { tag: String, line: usize },
+
+    #[error("partial `{path}` included at line {line} could not be read: {reason}")]
+    UnreadablePartial {
+        path: String,
+        line: usize,
+        reason: String,
+    },
+
+    #[error("in partial `{path}`: {error}")]
+    InPartial {
+        path: String,
+        error: Box<TemplateError>,
+    },
+
+    #[error("partials include each other: {}", .chain.join(" -> "))]
+    PartialCycle { chain: Vec<String> },
+
+    #[error("`{tag}` at line {line} has to be at the top level of the template, only once")]
+    MisplacedExtends { tag: String, line: usize },
+
+    #[error(
+        "`{content}` is outside `block`, which is never rendered in the template with `extends`"
+    )]
+    OutsideBlock { content: String },
+
+    #[error("block `{name}` is defined twice")]
+    DuplicateBlock { name: String },
+
+    #[error("block `{name}` is not in the layout `{layout}`")]
+    UndefinedBlock { name: String, layout: String },
+
+    #[error("layout `{path}` extended at line {line} could not be read: {reason}")]
+    UnreadableLayout {
+        path: String,
+        line: usize,
+        reason: String,
+    },
+
+    #[error("in layout `{path}`: {error}")]
+    InLayout {
+        path: String,
+        error: Box<TemplateError>,
+    },
+
+    #[error("layouts extend each other: {}", .chain.join(" -> "))]
+    LayoutCycle { chain: Vec<String> },
+}
+
+/// Template parsed into blocks, which can be rendered many times.
+///
+/// ```html
+/// ${#if release}<script src="/analytics.js"></script>${/if}
+/// ${#if !extra.cover}<p>No cover</p>${#else}<img src="${extra.cover}">${/if}
+/// <ul>${#each tags}<li><a href="${url}">${name}</a></li>${/each}</ul>
+/// ${> partials/footer.html}
+/// ```
+///
+/// A page can also extend a layout, filling the blocks of the same name.
+/// The other blocks keep the content in the layout.
+///
+/// ```html
+/// ${extends _base.html}
+/// ${block content}<article>${content}</article>${endblock}
+/// ```
+///
+/// `#if` is true for the non-empty text other than `false`, or the non-empty list. Unknown names are false.
+/// `#each` renders nothing for the names which are not lists.
+/// Partials are parsed on their own, so their blocks have to be closed in the same file.
+/// They are rendered with the same values as the including template.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Template {
+    nodes: Vec<Node>,
+    dependencies: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Node {
+    Text(String),
+    Placeholder(String),
+    If {
+        name: String,
+        negated: bool,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    Each {
+        name: String,
+        body: Vec<Node>,
+    },
+    Block {
+        name: String,
+        body: Vec<Node>,
+    },
+    /// Replaced with the layout before the template is returned.
+    Extends {
+        path: String,
+        line: usize,
+    },
+}
+
+impl Node {
+    /// Short description of the node for the errors.
+    fn summary(&self) -> String {
+        match self {
+            Node::Text(text) => text
+                .trim()
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .chars()
+                .take(30)
+                .collect(),
+            Node::Placeholder(name) => name.clone(),
+            Node::If { name, .. } => format!("#if {name}"),
+            Node::Each { name, .. } => format!("#each {name}"),
+            Node::Block { name, .. } => format!("block {name}"),
+            Node::Extends { path, .. } => format!("extends {path}"),
+        }
+    }
+}
+
+/// Block being parsed, waiting for its closing tag.
+struct OpenBlock {
+    tag: String,
+    line: usize,
+    kind: BlockKind,
+    nodes: Vec<Node>,
+    otherwise: Option<Vec<Node>>,
+}
+
+enum BlockKind {
+    If { name: String, negated: bool },
+    Each { name: String },
+    Block { name: String },
+}
+
+impl OpenBlock {
+    fn nodes(&mut self) -> &mut Vec<Node> {
+        match &mut self.otherwise {
+            Some(otherwise) => otherwise,
+            None => &mut self.nodes,
+        }
+    }
+
+    fn close(self) -> Node {
+        match self.kind {
+            BlockKind::If { name, negated } => Node::If {
+                name,
+                negated,
+                then: self.nodes,
+                otherwise: self.otherwise.unwrap_or_default(),
+            },
+            BlockKind::Each { name } => Node::Each {
+                name,
+                body: self.nodes,
+            },
+            BlockKind::Block { name } => Node::Block {
+                name,
+                body: self.nodes,
+            },
+        }
+    }
+}
+
+/// Reads the partial or the layout at the path given in `${> path}` or `${extends path}`.
+pub type TemplateLoader<'l> = dyn FnMut(&str) -> Result<String, String> + 'l;
+
+impl Placeholder {
+    /// Parses `haystack` into a [`Template`]. `block` and the placeholders starting with `#` or `/` are the blocks.
+    /// `${> path}` is replaced with the partial, and `${extends path}` with the layout, both read by `load`.
+    pub fn parse_template(
+        &self,
+        haystack: &str,
+        load: &mut TemplateLoader,
+    ) -> Result<Template, TemplateError> {
+        let mut dependencies = vec![];
+        let nodes = self.parse_nodes(haystack, load, &mut vec![], &mut dependencies)?;
+        let nodes = self.extend_layout(nodes, load, &mut vec![], &mut dependencies)?;
+
+        Ok(Template {
+            nodes,
+            dependencies,
+        })
+    }
+
+    /// Fills the blocks in the layout given in `${extends}` of `nodes`, if any.
+    /// `extending` is the chain of the layouts being parsed, to detect the cycles.
+    fn extend_layout(
+        &self,
+        nodes: Vec<Node>,
+        load: &mut TemplateLoader,
+        extending: &mut Vec<String>,
+        dependencies: &mut Vec<String>,
+    ) -> Result<Vec<Node>, TemplateError> {
+        let Some((path, line)) = nodes.iter().find_map(|node| match node {
+            Node::Extends { path, line } => Some((path.clone(), *line)),
+            _ => None,
+        }) else {
+            return Ok(nodes);
+        };
+
+        let mut blocks = HashMap::new();
+        for node in nodes {
+            match node {
+                Node::Extends { .. } => {}
+                Node::Text(text) if text.trim().is_empty() => {}
+                Node::Block { name, body } => {
+                    if blocks.insert(name.clone(), body).is_some() {
+                        return Err(TemplateError::DuplicateBlock { name });
+                    }
+                }
+                node => {
+                    return Err(TemplateError::OutsideBlock {
+                        content: node.summary(),
+                    });
+                }
+            }
+        }
+
+        if extending.contains(&path) {
+            let mut chain = extending.clone();
+            chain.push(path);
+            return Err(TemplateError::LayoutCycle { chain });
+        }
+
+        let source = load(&path).map_err(|reason| TemplateError::UnreadableLayout {
+            path: path.clone(),
+            line,
+            reason,
+        })?;
+
+        extending.push(path.clone());
+        let layout = self
+            .parse_nodes(&source, load, &mut vec![], dependencies)
+            .and_then(|nodes| self.extend_layout(nodes, load, extending, dependencies))
+            .map_err(|error| match error {
+                TemplateError::LayoutCycle { .. } => error,
+                error => TemplateError::InLayout {
+                    path: path.clone(),
+                    error: Box::new(error),
+                },
+            })?;
+        extending.pop();
+
+        let mut filled = vec![];
+        let layout = fill_blocks(layout, &blocks, &mut filled);
+
+        let mut undefined = blocks
+            .into_keys()
+            .filter(|name| !filled.contains(name))
+            .collect::<Vec<_>>();
+        undefined.sort();
+        if let Some(name) = undefined.into_iter().next() {
+            return Err(TemplateError::UndefinedBlock { name, layout: path });
+        }
+
+        if !dependencies.contains(&path) {
+            dependencies.push(path);
+        }
+
+        Ok(layout)
+    }
+
+    /// `including` is the chain of the partials being parsed, to detect the cycles.
+    fn parse_nodes(
+        &self,
+        haystack: &str,
+        load: &mut TemplateLoader,
+        including: &mut Vec<String>,
+        dependencies: &mut Vec<String>,
+    ) -> Result<Vec<Node>, TemplateError> {
+        let mut root = vec![];
+        let mut stack: Vec<OpenBlock> = vec![];
+        let mut last_end = 0;
+
+        for capture in self.regex.captures_iter(haystack) {
+            let whole = capture
+                .get(0)
+                .expect("Capture group 0 is always the whole match");
+            let tag = capture
+                .name(REGEX_CAPTURE_GROUP)
+                .expect("Regex is validated to include the capture group")
+                .as_str();
+            let line = haystack[..whole.start()].matches('\n').count() + 1;
+
+            if last_end < whole.start() {
+                let text = Node::Text(haystack[last_end..whole.start()].to_string());
+                stack
+                    .last_mut()
+                    .map_or(&mut root, OpenBlock::nodes)
+                    .push(text);
+            }
+            last_end = whole.end();
+
+            let (keyword, name) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            let name = name.trim();
+            let missing_name = || TemplateError::MissingName {
+                tag: tag.to_string(),
+                line,
+            };
+
+            if let Some(path) = tag.strip_prefix('>').map(str::trim) {
+                if path.is_empty() {
+                    return Err(missing_name());
+                }
+
+                if including.iter().any(|included| included == path) {
+                    let mut chain = including.clone();
+                    chain.push(path.to_string());
+                    return Err(TemplateError::PartialCycle { chain });
+                }
+
+                let source = load(path).map_err(|reason| TemplateError::UnreadablePartial {
+                    path: path.to_string(),
+                    line,
+                    reason,
+                })?;
+
+                including.push(path.to_string());
+                let nodes = self
+                    .parse_nodes(&source, load, including, dependencies)
+                    .map_err(|error| match error {
+                        TemplateError::PartialCycle { .. } => error,
+                        error => TemplateError::InPartial {
+                            path: path.to_string(),
+                            error: Box::new(error),
+                        },
+                    })?;
+                including.pop();
+
+                if !dependencies.iter().any(|dependency| dependency == path) {
+                    dependencies.push(path.to_string());
+                }
+                stack
+                    .last_mut()
+                    .map_or(&mut root, OpenBlock::nodes)
+                    .extend(nodes);
+                continue;
+            }
+
+            match keyword {
+                "#if" | "#each" => {
+                    if name.is_empty() || name == "!" {
+                        return Err(missing_name());
+                    }
+
+                    let kind = match (keyword, name.strip_prefix('!')) {
+                        ("#if", Some(name)) => BlockKind::If {
+                            name: name.trim().to_string(),
+                            negated: true,
+                        },
+                        ("#if", None) => BlockKind::If {
+                            name: name.to_string(),
+                            negated: false,
+                        },
+                        _ => BlockKind::Each {
+                            name: name.to_string(),
+                        },
+                    };
+
+                    stack.push(OpenBlock {
+                        tag: tag.to_string(),
+                        line,
+                        kind,
+                        nodes: vec![],
+                        otherwise: None,
+                    });
+                }
+                "block" => {
+                    if name.is_empty() {
+                        return Err(missing_name());
+                    }
+
+                    stack.push(OpenBlock {
+                        tag: tag.to_string(),
+                        line,
+                        kind: BlockKind::Block {
+                            name: name.to_string(),
+                        },
+                        nodes: vec![],
+                        otherwise: None,
+                    });
+                }
+                "extends" => {
+                    if name.is_empty() {
+                        return Err(missing_name());
+                    }
+
+                    let extended = root.iter().any(|node| matches!(node, Node::Extends { .. }));
+                    if !stack.is_empty() || !including.is_empty() || extended {
+                        return Err(TemplateError::MisplacedExtends {
+                            tag: tag.to_string(),
+                            line,
+                        });
+                    }
+
+                    root.push(Node::Extends {
+                        path: name.to_string(),
+                        line,
+                    });
+                }
+                "#else" => match stack.last_mut() {
+                    Some(
+                        block @ OpenBlock {
+                            kind: BlockKind::If { .. },
+                            otherwise: None,
+                            ..
+                        },
+                    ) => {
+                        block.otherwise = Some(vec![]);
+                    }
+                    _ => {
+                        return Err(TemplateError::MisplacedElse {
+                            tag: tag.to_string(),
+                            line,
+                        });
+                    }
+                },
+                "/if" | "/each" | "endblock" => {
+                    let Some(block) = stack.pop() else {
+                        return Err(TemplateError::UnexpectedClose {
+                            tag: tag.to_string(),
+                            line,
+                        });
+                    };
+
+                    let matches = matches!(
+                        (keyword, &block.kind),
+                        ("/if", BlockKind::If { .. })
+                            | ("/each", BlockKind::Each { .. })
+                            | ("endblock", BlockKind::Block { .. })
+                    );
+                    if !matches {
+                        return Err(TemplateError::MismatchedClose {
+                            tag: tag.to_string(),
+                            line,
+                            open: block.tag,
+                            open_line: block.line,
+                        });
+                    }
+
+                    let node = block.close();
+                    stack
+                        .last_mut()
+                        .map_or(&mut root, OpenBlock::nodes)
+                        .push(node);
+                }
+                _ if keyword.starts_with(['#', '/']) => {
+                    return Err(TemplateError::UnknownBlock {
+                        tag: tag.to_string(),
+                        line,
+                    });
+                }
+                _ => {
+                    let placeholder = Node::Placeholder(tag.to_string());
+                    stack
+                        .last_mut()
+                        .map_or(&mut root, OpenBlock::nodes)
+                        .push(placeholder);
+                }
+            }
+        }
+
+        if let Some(block) = stack.pop() {
+            return Err(TemplateError::Unclosed {
+                tag: block.tag,
+                line: block.line,
+            });
+        }
+
+        if last_end < haystack.len() {
+            root.push(Node::Text(haystack[last_end..].to_string()));
+        }
+
+        Ok(root)
+    }
+}
+
+impl Template {
+    /// Paths of the partials and the layouts read while parsing, including the indirect ones.
+    pub fn dependencies(&self) -> &[String] {
+        &self.dependencies
+    }
+
+    /// Renders with the values in `context`. Returns the names of the unknown placeholders if any.
+    pub fn render(&self, context: &impl Context) -> Result<String, Vec<String>> {
+        let mut rendered = String::new();
+        let mut failed_replaces = vec![];
+
+        render_nodes(
+            &self.nodes,
+            context,
+            &[],
+            &mut rendered,
+            &mut failed_replaces,
+        );
+
+        if failed_replaces.is_empty() {
+            Ok(rendered)
+        } else {
+            Err(failed_replaces)
+        }
+    }
+}
+
+fn render_nodes(
+    nodes: &[Node],
+    context: &impl Context,
+    scopes: &[&Item],
+    rendered: &mut String,
+    failed_replaces: &mut Vec<String>,
+) {
+    let text = |name: &str| {
+        scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).cloned())
+            .or_else(|| context.text(name))
+    };
+
+    for node in nodes {
+        match node {
+            Node::Text(text) => rendered.push_str(text),
+            Node::Placeholder(name) => match text(name) {
+                Some(text) => rendered.push_str(&text),
+                None => failed_replaces.push(name.clone()),
+            },
+            Node::If {
+                name,
+                negated,
+                then,
+                otherwise,
+            } => {
+                let is_truthy = |text: &str| !text.is_empty() && text != "false";
+                let truthy = match scopes.iter().rev().find_map(|scope| scope.get(name)) {
+                    Some(text) => is_truthy(text),
+                    None => match context.items(name) {
+                        Some(items) => !items.is_empty(),
+                        None => context.text(name).is_some_and(|text| is_truthy(&text)),
+                    },
+                };
+                let branch = if truthy != *negated { then } else { otherwise };
+
+                render_nodes(branch, context, scopes, rendered, failed_replaces);
+            }
+            Node::Each { name, body } => {
+                for item in context.items(name).unwrap_or_default() {
+                    let scopes = scopes.iter().copied().chain([&item]).collect::<Vec<_>>();
+                    render_nodes(body, context, &scopes, rendered, failed_replaces);
+                }
+            }
+            Node::Block { body, .. } => {
+                render_nodes(body, context, scopes, rendered, failed_replaces)
+            }
+            Node::Extends { .. } => {}
+        }
+    }
+}
+
+/// Replaces the bodies of the blocks in `layout` with the ones in `blocks`, recording the names of the replaced ones.
+fn fill_blocks(
+    layout: Vec<Node>,
+    blocks: &HashMap<String, Vec<Node>>,
+    filled: &mut Vec<String>,
+) -> Vec<Node> {
+    layout
+        .into_iter()
+        .map(|node| match node {
+            Node::Block { name, body } => {
+                let body = match blocks.get(&name) {
+                    Some(body) => {
+                        filled.push(name.clone());
+                        body.clone()
+                    }
+                    None => fill_blocks(body, blocks, filled),
+                };
+
+                Node::Block { name, body }
+            }
+            Node::If {
+                name,
+                negated,
+                then,
+                otherwise,
+            } => Node::If {
+                name,
+                negated,
+                then: fill_blocks(then, blocks, filled),
+                otherwise: fill_blocks(otherwise, blocks, filled),
+            },
+            Node::Each { name, body } => Node::Each {
+                name,
+                body: fill_blocks(body, blocks, filled),
+            },
+            node => node,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{Context, Item, TemplateError, TemplateLoader};
+    use crate::Placeholder;
+
+    struct Page(HashMap<&'static str, String>);
+
+    impl Context for Page {
+        fn text(&self, name: &str) -> Option<String> {
+            self.0.text(name)
+        }
+
+        fn items(&self, name: &str) -> Option<Vec<Item>> {
+            (name == "tags").then(|| {
+                ["rust", "web"]
+                    .map(|tag| Item::from([("name".to_string(), tag.to_string())]))
+                    .to_vec()
+            })
+        }
+    }
+
+    fn no_partials(path: &str) -> Result<String, String> {
+        Err(format!("{path} is not found"))
+    }
+
+    fn placeholder() -> Placeholder {
+        Placeholder::from_strs("${", "}", None)
+            .expect("Placeholder::from_strs not to error for valid arguments.")
+    }
+
+    #[test]
+    pub fn renders_conditionals_and_loops() {
+        let page = Page(HashMap::from([
+            ("release", "true".to_string()),
+            ("debug", "false".to_string()),
+            ("title", "Hello".to_string()),
+        ]));
+        let template = placeholder()
+            .parse_template(
+                "${#if release}<!-- ${title} -->${/if}${#if debug}D${#else}R${/if}${#if !cover}-${/if}\
+                 ${#each tags}[${name}/${title}${#if name}!${/if}]${/each}${#each missing}x${/each}",
+                &mut no_partials,
+            )
+            .expect("Template to be valid");
+
+        assert_eq!(
+            template.render(&page),
+            Ok("<!-- Hello -->R-[rust/Hello!][web/Hello!]".to_string())
+        );
+        assert_eq!(
+            placeholder()
+                .parse_template(
+                    "${#if release}${unknown}${/if}${#if debug}${ignored}${/if}",
+                    &mut no_partials
+                )
+                .expect("Template to be valid")
+                .render(&page),
+            Err(vec!["unknown".to_string()])
+        );
+    }
+
+    #[test]
+    pub fn reports_unbalanced_blocks() {
+        let parse = |source| placeholder().parse_template(source, &mut no_partials).err();
+
+        assert_eq!(
+            parse("${#if release}\n${#each tags}\n${/if}"),
+            Some(TemplateError::MismatchedClose {
+                tag: "/if".to_string(),
+                line: 3,
+                open: "#each tags".to_string(),
+                open_line: 2,
+            })
+        );
+        assert_eq!(
+            parse("a\n${#if release}"),
+            Some(TemplateError::Unclosed {
+                tag: "#if release".to_string(),
+                line: 2,
+            })
+        );
+        assert_eq!(
+            parse("${/each}"),
+            Some(TemplateError::UnexpectedClose {
+                tag: "/each".to_string(),
+                line: 1,
+            })
+        );
+        assert_eq!(
+            parse("${#each tags}${#else}${/each}"),
+            Some(TemplateError::MisplacedElse {
+                tag: "#else".to_string(),
+                line: 1,
+            })
+        );
+        assert_eq!(
+            parse("${#unless release}"),
+            Some(TemplateError::UnknownBlock {
+                tag: "#unless release".to_string(),
+                line: 1,
+            })
+        );
+        assert_eq!(
+            parse("${#if}"),
+            Some(TemplateError::MissingName {
+                tag: "#if".to_string(),
+                line: 1,
+            })
+        );
+    }
+
+    #[test]
+    pub fn includes_partials() {
+        let partials = HashMap::from([
+            ("head.html", "<title>${title}</title>${> meta.html}"),
+            ("meta.html", "${#each tags}<meta name=\"${name}\">${/each}"),
+            ("loop.html", "${> loop-again.html}"),
+            ("loop-again.html", "${> loop.html}"),
+            ("broken.html", "a\n${#if release}"),
+        ]);
+        let mut load = |path: &str| match partials.get(path) {
+            Some(source) => Ok(source.to_string()),
+            None => no_partials(path),
+        };
+        let page = Page(HashMap::from([("title", "Hello".to_string())]));
+
+        let template = placeholder()
+            .parse_template("<head>${> head.html}</head>${>meta.html}", &mut load)
+            .expect("Template to be valid");
+        let meta = r#"<meta name="rust"><meta name="web">"#;
+        assert_eq!(
+            template.render(&page),
+            Ok(format!("<head><title>Hello</title>{meta}</head>{meta}"))
+        );
+        assert_eq!(template.dependencies(), ["meta.html", "head.html"]);
+
+        assert_eq!(
+            placeholder()
+                .parse_template("${> loop.html}", &mut load)
+                .err(),
+            Some(TemplateError::PartialCycle {
+                chain: ["loop.html", "loop-again.html", "loop.html"]
+                    .map(String::from)
+                    .to_vec(),
+            })
+        );
+        assert_eq!(
+            placeholder()
+                .parse_template("${> broken.html}", &mut load)
+                .err(),
+            Some(TemplateError::InPartial {
+                path: "broken.html".to_string(),
+                error: Box::new(TemplateError::Unclosed {
+                    tag: "#if release".to_string(),
+                    line: 2,
+                }),
+            })
+        );
+        assert_eq!(
+            placeholder()
+                .parse_template("\n${> missing.html}", &mut load)
+                .err(),
+            Some(TemplateError::UnreadablePartial {
+                path: "missing.html".to_string(),
+                line: 2,
+                reason: "missing.html is not found".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    pub fn extends_layouts() {
+        let layouts = HashMap::from([
+            (
+                "_base.html",
+                "<title>${block title}Site${endblock}</title>${block body}${endblock}${> footer.html}",
+            ),
+            (
+                "_page.html",
+                "${extends _base.html}${block body}<main>${block main}${endblock}</main>${endblock}",
+            ),
+            ("footer.html", "<footer>${title}</footer>"),
+            ("_loop.html", "${extends _loop.html}"),
+            ("_broken.html", "${block body}"),
+        ]);
+        let mut load = |path: &str| match layouts.get(path) {
+            Some(source) => Ok(source.to_string()),
+            None => no_partials(path),
+        };
+        let page = Page(HashMap::from([("title", "Hello".to_string())]));
+
+        let template = placeholder()
+            .parse_template(
+                "${extends _page.html}\n${block main}${#if title}${title}${/if}${endblock}\n",
+                &mut load,
+            )
+            .expect("Template to be valid");
+        assert_eq!(
+            template.render(&page),
+            Ok("<title>Site</title><main>Hello</main><footer>Hello</footer>".to_string())
+        );
+        assert_eq!(
+            template.dependencies(),
+            ["footer.html", "_base.html", "_page.html"]
+        );
+
+        let parse =
+            |source, load: &mut TemplateLoader| placeholder().parse_template(source, load).err();
+        assert_eq!(
+            parse(
+                "${extends _page.html}${block sidebar}${endblock}",
+                &mut load
+            ),
+            Some(TemplateError::UndefinedBlock {
+                name: "sidebar".to_string(),
+                layout: "_page.html".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("${extends _page.html}<p>${title}</p>", &mut load),
+            Some(TemplateError::OutsideBlock {
+                content: "<p>".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("${extends _loop.html}", &mut load),
+            Some(TemplateError::LayoutCycle {
+                chain: ["_loop.html", "_loop.html"].map(String::from).to_vec(),
+            })
+        );
+        assert_eq!(
+            parse("${extends _broken.html}", &mut load),
+            Some(TemplateError::InLayout {
+                path: "_broken.html".to_string(),
+                error: Box::new(TemplateError::Unclosed {
+                    tag: "block body".to_string(),
+                    line: 1,
+                }),
+            })
+        );
+        assert_eq!(
+            parse("\n${extends _missing.html}", &mut load),
+            Some(TemplateError::UnreadableLayout {
+                path: "_missing.html".to_string(),
+                line: 2,
+                reason: "_missing.html is not found".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("${block body}${extends _base.html}${endblock}", &mut load),
+            Some(TemplateError::MisplacedExtends {
+                tag: "extends _base.html".to_string(),
+                line: 1,
+            })
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use loss72_platemaker_core::{log, model::GenerationContext};
+use loss72_platemaker_template::Template;
+use loss72_platemaker_widgets::ai::AiUsage;
+use serde::Serialize;
+
+use crate::{
+    OutputResult, WebPageHtmlTemplates, WebsiteGenerationError,
+    articles::{ArticlePage, IndexPage, article_url, render_article_list, render_listing_page},
+};
+
+#[derive(Debug)]
+pub struct AiUsageHtmlTemplates {
+    pub ai_usage: Template,
+    pub ai_usage_list: Template,
+}
+
+/// Same data as the report page, written to `/ai-usage/index.json`.
+#[derive(Debug, Serialize)]
+struct AiUsageReport<'a> {
+    levels: Vec<LevelReport<'a>>,
+    timeline: Vec<PeriodReport>,
+}
+
+#[derive(Debug, Serialize)]
+struct LevelReport<'a> {
+    level: &'static str,
+    brief: &'a str,
+    description: &'a str,
+    articles: Vec<ArticleEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct ArticleEntry {
+    title: String,
+    url: String,
+    date: String,
+}
+
+/// Number of the articles in each level, for the articles published in the month.
+#[derive(Debug, Serialize)]
+struct PeriodReport {
+    period: String,
+    counts: Vec<LevelCount>,
+}
+
+#[derive(Debug, Serialize)]
+struct LevelCount {
+    level: &'static str,
+    count: usize,
+}
+
+/// Generates `/ai-usage/index.html` grouping the articles by `widgets.ai`, with the counts for each month,
+/// and `/ai-usage/index.json` with the same data. Only the articles in the default language are counted.
+///
+/// Returns nothing if the AI usage templates are not provided.
+pub fn generate_ai_usage_pages(
+    html_templates: &WebPageHtmlTemplates,
+    articles: &[ArticlePage],
+    ctx: &GenerationContext,
+) -> OutputResult<Vec<IndexPage>> {
+    let Some(ai_usage_templates) = &html_templates.ai_usage else {
+        return Ok(vec![]);
+    };
+
+    log!(section: "Generating AI usage report");
+
+    let messages = html_templates.messages.messages(None);
+
+    let listed = articles
+        .iter()
+        .filter(|page| !page.article.metadata.unlisted && page.article.id.lang.is_none())
+        .collect::<Vec<_>>();
+    let pages_of = |level: &AiUsage| {
+        listed
+            .iter()
+            .copied()
+            .filter(|page| page.article.metadata.widgets.ai == *level)
+            .collect::<Vec<_>>()
+    };
+
+    let mut periods = listed
+        .iter()
+        .map(|page| page.article.id.date.format("%Y-%m").to_string())
+        .collect::<Vec<_>>();
+    periods.sort();
+    periods.dedup();
+
+    let report = AiUsageReport {
+        levels: AiUsage::ALL
+            .iter()
+            .map(|level| {
+                let (brief, description) = level.description(&messages);
+
+                LevelReport {
+                    level: level.name(),
+                    brief,
+                    description,
+                    articles: pages_of(level)
+                        .iter()
+                        .map(|page| ArticleEntry {
+                            title: page.article.metadata.title.clone(),
+                            url: article_url(page.article),
+                            date: page.article.id.date.format("%Y-%m-%d").to_string(),
+                        })
+                        .collect(),
+                }
+            })
+            .collect(),
+        timeline: periods
+            .into_iter()
+            .map(|period| PeriodReport {
+                counts: AiUsage::ALL
+                    .iter()
+                    .map(|level| LevelCount {
+                        level: level.name(),
+                        count: pages_of(level)
+                            .iter()
+                            .filter(|page| {
+                                page.article.id.date.format("%Y-%m").to_string() == period
+                            })
+                            .count(),
+                    })
+                    .collect(),
+                period,
+            })
+            .collect(),
+    };
+
+    let levels = AiUsage::ALL
+        .iter()
+        .zip(&report.levels)
+        .filter(|(_, level_report)| !level_report.articles.is_empty())
+        .map(|(level, level_report)| {
+            Ok(format!(
+                r#"<section class="ai-usage-level" id="ai-usage-{}"><h2>{}</h2><p>{}</p><ul>{}</ul></section>"#,
+                level_report.level,
+                level_report.brief,
+                level_report.description,
+                render_article_list(&ai_usage_templates.ai_usage_list, pages_of(level), html_templates, ctx)?,
+            ))
+        })
+        .collect::<OutputResult<String>>()?;
+
+    let timeline = format!(
+        r#"<table class="ai-usage-timeline"><thead><tr><th>{}</th>{}</tr></thead><tbody>{}</tbody></table>"#,
+        messages.get("ai_usage.period"),
+        report
+            .levels
+            .iter()
+            .map(|level| format!(r#"<th scope="col">{}</th>"#, level.brief))
+            .collect::<String>(),
+        report
+            .timeline
+            .iter()
+            .map(|period| {
+                format!(
+                    r#"<tr><th scope="row">{}</th>{}</tr>"#,
+                    period.period,
+                    period
+                        .counts
+                        .iter()
+                        .map(|count| format!("<td>{}</td>", count.count))
+                        .collect::<String>()
+                )
+            })
+            .collect::<String>(),
+    );
+
+    let placeholder_contents = HashMap::from([
+        ("levels", levels),
+        ("timeline", timeline),
+        ("count", listed.len().to_string()),
+        ("json_url", "/ai-usage/index.json".to_string()),
+        ("style", html_templates.index_style.clone()),
+    ]);
+    let html = render_listing_page(
+        &ai_usage_templates.ai_usage,
+        PathBuf::from("ai-usage").join("index.html"),
+        placeholder_contents,
+        ctx,
+    )?;
+    let json = IndexPage {
+        path: PathBuf::from("ai-usage").join("index.json"),
+        html: serde_json::to_string_pretty(&report)
+            .map_err(WebsiteGenerationError::InvalidReport)?,
+    };
+
+    log!(ok: "Generated AI usage report of {} articles", listed.len());
+
+    Ok(vec![html, json])
+}
@@ -0,0 +1,228 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use loss72_platemaker_core::{
+    fs::Directory,
+    log,
+    model::{Article, GenerationContext},
+};
+use loss72_platemaker_template::Template;
+use loss72_platemaker_widgets::{
+    Widget,
+    article_type::{ArticleTypeDefinition, ArticleTypeHeading, builtin_article_types},
+    messages::Messages,
+};
+
+use crate::{
+    OutputResult, WebPageHtmlTemplates, WebsiteGenerationError,
+    articles::{ArticlePage, IndexPage, render_index_list, render_listing_page},
+    check_url_dir_names, read_template, url_dir_name,
+};
+
+#[derive(Debug)]
+pub struct TypeHtmlTemplates {
+    pub type_page: Template,
+}
+
+/// Article types declared in the configuration, or the built-in ones if none is declared.
+#[derive(Debug, Default)]
+pub struct ArticleTypes {
+    entries: BTreeMap<String, ArticleTypeEntry>,
+    /// Type of the articles without `widgets.article_type`.
+    default: String,
+}
+
+#[derive(Debug)]
+struct ArticleTypeEntry {
+    definition: ArticleTypeDefinition,
+    list_template: Option<Template>,
+}
+
+impl ArticleTypes {
+    /// Reads the alternate list templates of the types from `template_dir`. `default` has to be one of the types.
+    pub fn load(
+        template_dir: &Directory,
+        definitions: &BTreeMap<String, ArticleTypeDefinition>,
+        default: &str,
+    ) -> OutputResult<Self> {
+        let definitions = if definitions.is_empty() {
+            builtin_article_types()
+        } else {
+            definitions.clone()
+        };
+
+        if !definitions.contains_key(default) {
+            return Err(WebsiteGenerationError::UnknownDefaultArticleType(
+                default.to_string(),
+            ));
+        }
+
+        let entries = definitions
+            .into_iter()
+            .map(|(name, definition)| {
+                let list_template = match &definition.list_template {
+                    Some(path) => {
+                        let [list_template] = template_dir.get_files(&[path])?;
+                        Some(read_template(template_dir, &list_template)?)
+                    }
+                    None => None,
+                };
+
+                Ok((
+                    name,
+                    ArticleTypeEntry {
+                        definition,
+                        list_template,
+                    },
+                ))
+            })
+            .collect::<OutputResult<_>>()?;
+
+        Ok(Self {
+            entries,
+            default: default.to_string(),
+        })
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// Name of the type of the article, which is the default one if the article does not specify it.
+    pub fn name_of<'a>(&'a self, article: &'a Article) -> &'a str {
+        match &article.metadata.widgets.article_type {
+            Some(article_type) => &article_type.0,
+            None => &self.default,
+        }
+    }
+
+    /// The type of the article, which has to be declared.
+    pub fn of(&self, article: &Article) -> OutputResult<(&str, &ArticleTypeDefinition)> {
+        let name = self.name_of(article);
+
+        self.entries
+            .get_key_value(name)
+            .map(|(name, entry)| (name.as_str(), &entry.definition))
+            .ok_or_else(|| WebsiteGenerationError::UnknownArticleType {
+                slug: article.id.slug.clone(),
+                name: name.to_string(),
+            })
+    }
+
+    /// The alternate `_index-list.html` for the type of the article, if any.
+    pub fn list_template(&self, article: &Article) -> Option<&Template> {
+        self.entries
+            .get(self.name_of(article))?
+            .list_template
+            .as_ref()
+    }
+
+    pub(crate) fn placeholder_content(
+        &self,
+        article: &Article,
+        messages: &Messages,
+    ) -> OutputResult<[(&'static str, String); 4]> {
+        let (name, definition) = self.of(article)?;
+        let heading = ArticleTypeHeading { name, definition };
+
+        Ok([
+            ("type_class", definition.class_name(name)),
+            (
+                "type_name",
+                definition.description(name, messages).to_string(),
+            ),
+            (
+                "type_url",
+                if definition.listing_page {
+                    type_url(name)
+                } else {
+                    String::new()
+                },
+            ),
+            heading.render_widget(messages),
+        ])
+    }
+}
+
+/// Generates `/types/<type>/index.html` for the types with `listing_page`, listing the articles
+/// in the default language with the same list templates as the index page.
+///
+/// Returns nothing if `_type.html` is not provided.
+pub fn generate_type_pages(
+    html_templates: &WebPageHtmlTemplates,
+    articles: &[ArticlePage],
+    ctx: &GenerationContext,
+) -> OutputResult<Vec<IndexPage>> {
+    let Some(type_templates) = &html_templates.types else {
+        if html_templates
+            .article_types
+            .entries
+            .values()
+            .any(|entry| entry.definition.listing_page)
+        {
+            log!(warn: "_type.html is not found; pages of the article types will not be generated");
+        }
+        return Ok(vec![]);
+    };
+
+    log!(section: "Generating HTML for article type pages");
+
+    let messages = html_templates.messages.messages(None);
+
+    check_url_dir_names(
+        "article type",
+        html_templates
+            .article_types
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.definition.listing_page)
+            .map(|(name, _)| name.as_str()),
+    )?;
+
+    let type_pages = html_templates
+        .article_types
+        .entries
+        .iter()
+        .filter(|(_, entry)| entry.definition.listing_page)
+        .map(|(name, entry)| {
+            log!(step: "Generating HTML for article type '{}'", name);
+
+            let pages = articles
+                .iter()
+                .filter(|page| {
+                    !page.article.metadata.unlisted
+                        && page.article.id.lang.is_none()
+                        && html_templates.article_types.name_of(page.article) == name
+                })
+                .collect::<Vec<_>>();
+
+            let placeholder_contents = HashMap::from([
+                (
+                    "type_name",
+                    entry.definition.description(name, &messages).to_string(),
+                ),
+                ("type_class", entry.definition.class_name(name)),
+                ("count", pages.len().to_string()),
+                (
+                    "articles",
+                    render_index_list(html_templates, pages.iter().copied(), ctx)?,
+                ),
+                ("style", html_templates.index_style.clone()),
+            ]);
+            let path = PathBuf::from("types")
+                .join(url_dir_name(name))
+                .join("index.html");
+            render_listing_page(&type_templates.type_page, path, placeholder_contents, ctx)
+        })
+        .collect::<OutputResult<Vec<_>>>()?;
+
+    log!(ok: "Generated {} article type pages", type_pages.len());
+
+    Ok(type_pages)
+}
+
+pub fn type_url(name: &str) -> String {
+    format!("/types/{}/", url_dir_name(name))
+}
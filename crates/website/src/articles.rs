@@ -1,13 +1,24 @@
+use chrono::{Datelike, NaiveTime};
 use loss72_platemaker_construct::ConstructFile;
-use loss72_platemaker_core::{log, model::{Article, GenerationContext}, util::get_slice_by_char};
-use loss72_platemaker_template::Placeholder;
+use loss72_platemaker_core::{
+    log,
+    model::{Article, ArticleIdentifier, GenerationContext},
+    util::get_slice_by_char,
+};
+use loss72_platemaker_template::{Context, Item, Placeholder, Template};
+use loss72_platemaker_widgets::{Widget, messages::Messages, staleness::Staleness};
 use std::{
     any::type_name,
     collections::HashMap,
     path::{Path, PathBuf},
 };
 
-use crate::{OutputResult, WebPageHtmlTemplates, WebsiteGenerationError};
+use crate::{
+    OutputResult, WebPageHtmlTemplates, WebsiteGenerationError,
+    relations::ArticleRelations,
+    series::{series_navigation, series_url},
+    tags::{render_tag_links, tag_url},
+};
 
 pub struct IndexPage {
     pub html: String,
@@ -57,26 +68,81 @@ impl std::fmt::Debug for ArticlePage<'_> {
     }
 }
 
+/// Generates the index page listing the articles in `lang`, at `/index.html` for the default language
+/// and at `/<lang>/index.html` for the others.
 pub fn generate_index_html(
     html_templates: &WebPageHtmlTemplates,
     article: &[ArticlePage],
+    lang: Option<&str>,
     ctx: &GenerationContext,
 ) -> OutputResult<IndexPage> {
-    log!(section: "Generating HTML for index page");
+    match lang {
+        Some(lang) => log!(section: "Generating HTML for index page in '{}'", lang),
+        None => log!(section: "Generating HTML for index page"),
+    }
 
     if ctx.release {
         log!(step: "Using release build!");
     }
 
-    let placeholder = Placeholder::from_strs("${", "}", None)
-        .expect("Regex is validated to include the capture group");
-
     // We create list elements first
-    let article_tag_iter = article
-        .iter()
+    let article_tag_iter = render_index_list(
+        html_templates,
+        article.iter().filter(|page| {
+            !page.article.metadata.unlisted && page.article.id.lang.as_deref() == lang
+        }),
+        ctx,
+    )?;
+
+    let placeholder_contents = HashMap::from([
+        ("articles", article_tag_iter),
+        ("style", html_templates.index_style.clone()),
+        ("lang", lang.unwrap_or(&ctx.default_language).to_string()),
+    ]);
+    let path = lang
+        .map(|lang| Path::new(lang).join("index.html"))
+        .unwrap_or_else(|| PathBuf::from("index.html"));
+
+    render_listing_page(&html_templates.index, path, placeholder_contents, ctx)
+}
+
+/// Renders the pages with `_index-list.html`, or the alternate one for the article type.
+pub(crate) fn render_index_list<'a, 'article: 'a>(
+    html_templates: &WebPageHtmlTemplates,
+    pages: impl IntoIterator<Item = &'a ArticlePage<'article>>,
+    ctx: &GenerationContext,
+) -> OutputResult<String> {
+    pages
+        .into_iter()
+        .map(|page| {
+            let list_template = html_templates
+                .article_types
+                .list_template(page.article)
+                .unwrap_or(&html_templates.index_list);
+
+            render_article_list(list_template, [page], html_templates, ctx)
+        })
+        .collect()
+}
+
+pub(crate) fn render_article_list<'a, 'article: 'a>(
+    list_template: &Template,
+    pages: impl IntoIterator<Item = &'a ArticlePage<'article>>,
+    html_templates: &WebPageHtmlTemplates,
+    ctx: &GenerationContext,
+) -> OutputResult<String> {
+    pages
+        .into_iter()
         .map(|page| {
-            let mut placeholder_contents = article_to_placeholder_content(page.article, ctx);
-                placeholder_contents.insert(
+            let messages = html_templates.messages.messages(page.article.locale());
+            let mut placeholder_contents =
+                article_to_placeholder_content(page.article, &messages, ctx);
+            placeholder_contents.extend(
+                html_templates
+                    .article_types
+                    .placeholder_content(page.article, &messages)?,
+            );
+            placeholder_contents.insert(
                 "url",
                 Path::new("/articles")
                     .join(&page.path)
@@ -84,99 +150,481 @@ pub fn generate_index_html(
                     .to_string(),
             );
 
-            placeholder
-                .partially_fill_placeholders(&html_templates.index_list, |name| {
-                    placeholder_contents.get(name).cloned()
+            list_template
+                .render(&ArticleContext {
+                    article: page.article,
+                    placeholder_contents: &placeholder_contents,
                 })
-                .map_err(|invalids| WebsiteGenerationError::InvalidPlaceholder(invalids.clone()))
+                .map_err(WebsiteGenerationError::InvalidPlaceholder)
         })
-        .collect::<Result<String, _>>()?;
+        .collect::<Result<String, _>>()
+}
 
-    let placeholder_contents = HashMap::from([
-        ("articles", article_tag_iter),
-        ("style", html_templates.index_style.clone()),
+/// Renders the index page, or the listing page of the tag and the like, at `path` in the output directory.
+pub(crate) fn render_listing_page(
+    template: &Template,
+    path: PathBuf,
+    mut placeholder_contents: HashMap<&str, String>,
+    ctx: &GenerationContext,
+) -> OutputResult<IndexPage> {
+    placeholder_contents.extend(listing_condition_placeholders(ctx));
+
+    Ok(IndexPage {
+        path,
+        html: template
+            .render(&placeholder_contents)
+            .map_err(WebsiteGenerationError::InvalidPlaceholder)?,
+    })
+}
+
+/// `release` and `debug` for `${#if}`. The comment-based `${if-debug}` and the like are kept for the older templates.
+fn listing_condition_placeholders(ctx: &GenerationContext) -> [(&'static str, String); 6] {
+    [
+        ("release", ctx.release.to_string()),
+        ("debug", (!ctx.release).to_string()),
         ("if-debug", if ctx.release { "<!-- (if-debug: false) ".to_string() } else { "".to_string() }),
         ("end-if-debug", if ctx.release { " (end-if-debug: false) -->".to_string() } else { "".to_string() }),
         ("if-release", if ctx.release { "".to_string() } else { "<!-- (if-release: false) ".to_string() }),
         ("end-if-release", if ctx.release { "".to_string() } else { " (end-if-release: false) -->".to_string() }),
-    ]);
-
-    Ok(IndexPage {
-        path: PathBuf::from("index.html"),
-        html: placeholder
-            .partially_fill_placeholders(&html_templates.index, |name| {
-                placeholder_contents.get(name).cloned()
-            })
-            .map_err(|invalids| WebsiteGenerationError::InvalidPlaceholder(invalids.clone()))?,
-    })
+    ]
 }
 
+/// `relations` should be built from all the articles on the site, not only the ones being generated.
 pub fn generate_article_html<'article>(
     html_templates: &WebPageHtmlTemplates,
     article: &'article Article,
+    relations: &ArticleRelations,
     ctx: &GenerationContext,
 ) -> OutputResult<ArticlePage<'article>> {
     log!(step: "Generating HTML for slug '{}'", &article.id.slug);
 
-    let path = Path::new(&article.id.group).join(format!("{}.html", &article.id.slug));
+    let path = article_path(&article.id);
 
-    let placeholder = Placeholder::from_strs("${", "}", None)
-        .expect("Regex is validated to include the capture group");
+    let messages = html_templates.messages.messages(article.locale());
 
-    let mut placeholder_contents = article_to_placeholder_content(article, ctx);
+    let mut placeholder_contents: HashMap<&str, String> =
+        article_to_placeholder_content(article, &messages, ctx);
+    placeholder_contents.extend(
+        html_templates
+            .article_types
+            .placeholder_content(article, &messages)?,
+    );
     placeholder_contents.insert("content", article.content.clone());
     placeholder_contents.insert(
-        "path", 
-                Path::new("/articles")
-                    .join(&path)
-                    .to_string_lossy()
-                    .to_string());
-    placeholder_contents.extend(article.metadata.widgets.render_to_placeholder_content());
+        "path",
+        Path::new("/articles")
+            .join(&path)
+            .to_string_lossy()
+            .to_string(),
+    );
+    placeholder_contents.extend(
+        article
+            .metadata
+            .widgets
+            .render_to_placeholder_content(&messages),
+    );
+
+    let changelog = article.metadata.changelog_widget();
+
+    let (_, article_type) = html_templates.article_types.of(article)?;
+    let staleness = if article_type.staleness_notice {
+        article.staleness_widget(ctx)
+    } else {
+        Staleness::default()
+    };
+
+    let byline = html_templates
+        .authors
+        .byline(article, html_templates.author_pages.is_some())?;
+
+    let (series, series_links) = series_navigation(relations, article);
+
+    let widgets = [
+        (changelog.render_widget(&messages), changelog.style()),
+        (staleness.render_widget(&messages), staleness.style()),
+        (byline.render_widget(&messages), byline.style()),
+        (series.render_widget(&messages), series.style()),
+    ];
+    for ((name, html), style) in widgets {
+        placeholder_contents.insert(name, html);
+        placeholder_contents
+            .entry("widget_styles")
+            .or_default()
+            .push_str(style);
+    }
+    placeholder_contents
+        .entry("widget_styles")
+        .or_default()
+        .extend(
+            html_templates
+                .user_widgets
+                .iter()
+                .map(|widget| widget.style.as_str()),
+        );
+
+    placeholder_contents.extend(series_links);
+    placeholder_contents.extend(neighbour_placeholder_content(relations, article));
+    placeholder_contents.extend(translation_placeholder_content(relations, article, ctx));
+
+    // User widgets come last so that they cannot silently replace the built-in placeholders
+    for (name, html) in render_user_widgets(html_templates, article)? {
+        if placeholder_contents.contains_key(name) {
+            return Err(WebsiteGenerationError::InvalidWidgetDefinition(format!(
+                "'{name}' is the name of a built-in placeholder"
+            )));
+        }
+        placeholder_contents.insert(name, html);
+    }
 
     Ok(ArticlePage {
         article,
-        html: placeholder
-            .partially_fill_placeholders(&html_templates.article, |name| {
-                placeholder_contents.get(name).cloned()
+        html: html_templates
+            .article
+            .render(&ArticleContext {
+                article,
+                placeholder_contents: &placeholder_contents,
             })
-            .map_err(|invalids| WebsiteGenerationError::InvalidPlaceholder(invalids.clone()))?,
+            .map_err(WebsiteGenerationError::InvalidPlaceholder)?,
         path,
     })
 }
 
-fn article_to_placeholder_content(article: &Article, ctx: &GenerationContext) -> HashMap<&'static str, String> {
-    let (year, month, day) = article.id.date;
+/// Values of the article for its page and the list items. Besides the placeholders, `${extra.$key}` looks up `extra`,
+/// and `${#each}` iterates `tags` and the arrays in `extra`.
+struct ArticleContext<'a> {
+    article: &'a Article,
+    placeholder_contents: &'a HashMap<&'a str, String>,
+}
+
+impl Context for ArticleContext<'_> {
+    fn text(&self, name: &str) -> Option<String> {
+        self.placeholder_contents
+            .get(name)
+            .cloned()
+            .or_else(|| extra_placeholder_content(self.article, name))
+    }
+
+    fn items(&self, name: &str) -> Option<Vec<Item>> {
+        if name == "tags" {
+            return Some(
+                self.article
+                    .metadata
+                    .tags
+                    .iter()
+                    .map(|tag| {
+                        Item::from([
+                            ("name".to_string(), tag.clone()),
+                            ("url".to_string(), tag_url(tag)),
+                        ])
+                    })
+                    .collect(),
+            );
+        }
+
+        match self
+            .article
+            .metadata
+            .extra_value(name.strip_prefix("extra.")?)?
+        {
+            toml::Value::Array(values) => Some(values.iter().map(extra_item).collect()),
+            _ => None,
+        }
+    }
+}
+
+/// Values in `extra` for `${extra.$key}`, where the nested keys are dotted. Strings are used as they are.
+fn extra_placeholder_content(article: &Article, name: &str) -> Option<String> {
+    article
+        .metadata
+        .extra_value(name.strip_prefix("extra.")?)
+        .map(extra_text)
+}
+
+fn extra_text(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// An element of an array in `extra`, whose fields are available if it is a table. The whole is `this`.
+fn extra_item(value: &toml::Value) -> Item {
+    let mut item = match value {
+        toml::Value::Table(table) => table
+            .iter()
+            .map(|(key, value)| (key.clone(), extra_text(value)))
+            .collect(),
+        _ => Item::new(),
+    };
+    item.insert("this".to_string(), extra_text(value));
+
+    item
+}
+
+/// Renders the user widgets declared in the configuration. The ones not used in the article are empty.
+fn render_user_widgets<'t>(
+    html_templates: &'t WebPageHtmlTemplates,
+    article: &Article,
+) -> OutputResult<Vec<(&'t str, String)>> {
+    let placeholder = Placeholder::from_strs("${", "}", None)
+        .expect("Regex is validated to include the capture group");
+
+    let mut errors = article
+        .metadata
+        .widgets
+        .user
+        .keys()
+        .filter(|name| {
+            !html_templates
+                .user_widgets
+                .iter()
+                .any(|widget| &widget.name == *name)
+        })
+        .map(|name| format!("widgets.{name} is not declared in the configuration"))
+        .collect::<Vec<_>>();
+
+    let mut rendered = vec![];
+    for widget in &html_templates.user_widgets {
+        let table = match article.metadata.widgets.user.get(&widget.name) {
+            Some(toml::Value::Table(table)) => table,
+            Some(_) => {
+                errors.push(format!("widgets.{} has to be a table", widget.name));
+                continue;
+            }
+            None => {
+                rendered.push((widget.name.as_str(), String::new()));
+                continue;
+            }
+        };
+
+        match widget.field_values(table) {
+            Ok(values) => rendered.push((
+                widget.name.as_str(),
+                placeholder
+                    .partially_fill_placeholders(&widget.html, |name| values.get(name).cloned())
+                    .map_err(WebsiteGenerationError::InvalidPlaceholder)?,
+            )),
+            Err(mut widget_errors) => errors.append(&mut widget_errors),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(WebsiteGenerationError::InvalidWidget {
+            slug: article.id.slug.clone(),
+            errors,
+        });
+    }
+
+    Ok(rendered)
+}
+
+/// `prev` is the older article, and `next` is the newer one.
+fn neighbour_placeholder_content(
+    relations: &ArticleRelations,
+    article: &Article,
+) -> [(&'static str, String); 4] {
+    let previous = relations.previous(article);
+    let next = relations.next(article);
+
+    [
+        ("prev_url", previous.map(article_url).unwrap_or_default()),
+        (
+            "prev_title",
+            previous
+                .map(|previous| previous.metadata.title.clone())
+                .unwrap_or_default(),
+        ),
+        ("next_url", next.map(article_url).unwrap_or_default()),
+        (
+            "next_title",
+            next.map(|next| next.metadata.title.clone())
+                .unwrap_or_default(),
+        ),
+    ]
+}
+
+/// Links to the translations of the article. Both are empty if the article has no translations.
+fn translation_placeholder_content(
+    relations: &ArticleRelations,
+    article: &Article,
+    ctx: &GenerationContext,
+) -> [(&'static str, String); 2] {
+    let translations = relations.translations(article);
+    if translations.len() < 2 {
+        return [
+            ("hreflang", String::new()),
+            ("language_switcher", String::new()),
+        ];
+    }
+
+    let lang_of = |translation: &Article| {
+        translation
+            .id
+            .lang
+            .clone()
+            .unwrap_or_else(|| ctx.default_language.clone())
+    };
+
+    let hreflang = translations
+        .iter()
+        .map(|translation| {
+            format!(
+                r#"<link rel="alternate" hreflang="{}" href="{}">"#,
+                lang_of(translation),
+                article_url(translation)
+            )
+        })
+        .collect::<String>();
+
+    let language_switcher = format!(
+        r#"<ul class="language-switcher">{}</ul>"#,
+        translations
+            .iter()
+            .map(|translation| {
+                let lang = lang_of(translation);
+                if translation.id == article.id {
+                    format!(
+                        r#"<li class="current" lang="{lang}" aria-current="page">{}</li>"#,
+                        lang.to_uppercase()
+                    )
+                } else {
+                    format!(
+                        r#"<li><a href="{}" hreflang="{lang}" lang="{lang}">{}</a></li>"#,
+                        article_url(translation),
+                        lang.to_uppercase()
+                    )
+                }
+            })
+            .collect::<String>()
+    );
+
+    [
+        ("hreflang", hreflang),
+        ("language_switcher", language_switcher),
+    ]
+}
+
+/// Path to the article page, relative to the articles directory.
+/// Translations are placed next to the one in the default language, as `<slug>.<lang>.html`.
+fn article_path(id: &ArticleIdentifier) -> PathBuf {
+    match &id.lang {
+        Some(lang) => Path::new(&id.group).join(format!("{}.{lang}.html", &id.slug)),
+        None => Path::new(&id.group).join(format!("{}.html", &id.slug)),
+    }
+}
+
+pub(crate) fn article_url(article: &Article) -> String {
+    Path::new("/articles")
+        .join(article_path(&article.id))
+        .to_string_lossy()
+        .to_string()
+}
+
+fn article_to_placeholder_content(
+    article: &Article,
+    messages: &Messages,
+    ctx: &GenerationContext,
+) -> HashMap<&'static str, String> {
+    let date = article.id.date;
+    let history = article.history.as_ref();
 
     HashMap::from([
+        ("title", article.metadata.title.clone()),
+        ("brief", article.metadata.brief.clone()),
+        ("tags", render_tag_links(&article.metadata.tags)),
         (
-            "type_class",
+            "series_name",
+
+            article.metadata.series.clone().unwrap_or_default(),
+        ),
+        (
+            "series_url",
             article
                 .metadata
-                .widgets
-                .article_type
-                .class_name()
-                .to_string(),
+                .series
+                .as_deref()
+                .map(series_url)
+                .unwrap_or_default(),
         ),
         (
-            "type_name",
+            "updated",
             article
                 .metadata
-                .widgets
-                .article_type
-                .description()
-                .to_string(),
+                .last_updated()
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
         ),
-        ("title", article.metadata.title.clone()),
-        ("brief", article.metadata.brief.clone()),
-        ("year", year.to_string()),
-        ("month", month.to_string()),
-        ("day", day.to_string()),
-        ("MM", format!("{:02}", month)),
-        ("DD", format!("{:02}", day)),
+        (
+            "git_created",
+            history
+                .map(|history| history.created.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+        ),
+        (
+            "git_updated",
+            history
+                .map(|history| history.updated.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+        ),
+        (
+            "git_commit",
+            history
+                .map(|history| history.commit.clone())
+                .unwrap_or_default(),
+        ),
+        (
+            "git_path",
+            history
+                .map(|history| history.path.clone())
+                .unwrap_or_default(),
+        ),
+        (
+            "draft",
+            if article.metadata.draft {
+                format!(
+                    r#"<div class="draft-banner">{}</div>"#,
+                    messages.get("draft.banner")
+                )
+            } else {
+                "".to_string()
+            },
+        ),
+        ("year", date.year().to_string()),
+        ("month", date.month().to_string()),
+        ("day", date.day().to_string()),
+        ("MM", format!("{:02}", date.month())),
+        ("DD", format!("{:02}", date.day())),
+        ("weekday", date.weekday().to_string()),
+        ("iso_date", date.format("%Y-%m-%d").to_string()),
+        (
+            "rfc3339",
+            article
+                .metadata
+                .publish_at
+                .unwrap_or_else(|| date.and_time(NaiveTime::MIN).and_utc().fixed_offset())
+                .to_rfc3339(),
+        ),
+        (
+            "seq",
+            article
+                .id
+                .seq
+                .map(|seq| seq.to_string())
+                .unwrap_or_default(),
+        ),
+        (
+            "lang",
+            article
+                .id
+                .lang
+                .clone()
+                .unwrap_or_else(|| ctx.default_language.clone()),
+        ),
+        ("release", ctx.release.to_string()),
+        ("debug", (!ctx.release).to_string()),
         ("if-debug", if ctx.release { "".to_string() } else { "<!-- (debug) ".to_string() }),
         ("end-if-debug", if ctx.release { "".to_string() } else { " (debug) -->".to_string() }),
         ("if-release", if ctx.release { "<!-- (release) ".to_string() } else { "".to_string() }),
         ("end-if-release", if ctx.release { " (release) -->".to_string() } else { "".to_string() }),
     ])
 }
-
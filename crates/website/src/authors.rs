@@ -0,0 +1,190 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use loss72_platemaker_core::{
+    log,
+    model::{Article, GenerationContext},
+};
+use loss72_platemaker_template::Template;
+use loss72_platemaker_widgets::author::{Byline, BylineAuthor};
+use serde::Deserialize;
+
+use crate::{
+    OutputResult, WebPageHtmlTemplates, WebsiteGenerationError,
+    articles::{ArticlePage, IndexPage, render_article_list, render_listing_page},
+    check_url_dir_names, url_dir_name,
+};
+
+#[derive(Debug)]
+pub struct AuthorHtmlTemplates {
+    pub author: Template,
+    pub author_list: Template,
+}
+
+/// Authors registered in `authors.toml` in the template directory, keyed by the name used in articles.
+///
+/// ```toml
+/// [alice]
+/// name = "Alice"
+/// avatar = "authors/alice.png"
+/// bio = "Writes about Rust."
+/// links = [{ label = "GitHub", url = "https://github.com/alice" }]
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Authors(BTreeMap<String, Author>);
+
+#[derive(Debug, Deserialize)]
+pub struct Author {
+    pub name: String,
+    /// Path to the image in the template directory.
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub bio: String,
+    #[serde(default)]
+    pub links: Vec<AuthorLink>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuthorLink {
+    pub label: String,
+    pub url: String,
+}
+
+impl Authors {
+    pub fn get(&self, key: &str) -> Option<&Author> {
+        self.0.get(key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Author)> {
+        self.0.iter().map(|(key, author)| (key.as_str(), author))
+    }
+
+    /// Builds the byline of the article. All the authors of the article have to be registered.
+    ///
+    /// The authors are linked only if `with_pages` is set, i.e. the author pages are generated.
+    pub(crate) fn byline(&self, article: &Article, with_pages: bool) -> OutputResult<Byline> {
+        let unknown_keys = article
+            .metadata
+            .authors
+            .iter()
+            .filter(|key| !self.0.contains_key(key.as_str()))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !unknown_keys.is_empty() {
+            return Err(WebsiteGenerationError::UnknownAuthors {
+                slug: article.id.slug.clone(),
+                keys: unknown_keys,
+            });
+        }
+
+        Ok(Byline(
+            article
+                .metadata
+                .authors
+                .iter()
+                .filter_map(|key| self.0.get_key_value(key.as_str()))
+                .map(|(key, author)| BylineAuthor {
+                    name: author.name.clone(),
+                    url: with_pages.then(|| author_url(key)),
+                    avatar_url: author.avatar_url(),
+                })
+                .collect(),
+        ))
+    }
+}
+
+impl Author {
+    pub fn avatar_url(&self) -> Option<String> {
+        self.avatar
+            .as_ref()
+            .map(|avatar| format!("/{}", avatar.trim_start_matches('/')))
+    }
+
+    fn links_html(&self) -> String {
+        if self.links.is_empty() {
+            return String::new();
+        }
+
+        format!(
+            r#"<ul class="author-links">{}</ul>"#,
+            self.links
+                .iter()
+                .map(|link| format!(
+                    r#"<li><a href="{}" rel="me">{}</a></li>"#,
+                    link.url, link.label
+                ))
+                .collect::<String>()
+        )
+    }
+}
+
+/// Generates `/authors/<author>/index.html` for each registered author, listing the articles in the default language.
+///
+/// Returns nothing if the author templates are not provided.
+pub fn generate_author_pages(
+    html_templates: &WebPageHtmlTemplates,
+    articles: &[ArticlePage],
+    ctx: &GenerationContext,
+) -> OutputResult<Vec<IndexPage>> {
+    let Some(author_templates) = &html_templates.author_pages else {
+        return Ok(vec![]);
+    };
+
+    log!(section: "Generating HTML for author pages");
+
+    check_url_dir_names("author", html_templates.authors.iter().map(|(key, _)| key))?;
+
+    let author_pages = html_templates
+        .authors
+        .iter()
+        .map(|(key, author)| {
+            log!(step: "Generating HTML for author '{}'", key);
+
+            let pages = articles
+                .iter()
+                .filter(|page| !page.article.metadata.unlisted && page.article.id.lang.is_none())
+                .filter(|page| {
+                    page.article
+                        .metadata
+                        .authors
+                        .iter()
+                        .any(|author| author == key)
+                })
+                .collect::<Vec<_>>();
+
+            let placeholder_contents = HashMap::from([
+                ("author_name", author.name.clone()),
+                ("author_bio", author.bio.clone()),
+                ("author_avatar", author.avatar_url().unwrap_or_default()),
+                ("author_links", author.links_html()),
+                ("count", pages.len().to_string()),
+                (
+                    "articles",
+                    render_article_list(
+                        &author_templates.author_list,
+                        pages.iter().copied(),
+                        html_templates,
+                        ctx,
+                    )?,
+                ),
+                ("style", html_templates.index_style.clone()),
+            ]);
+            let path = PathBuf::from("authors")
+                .join(url_dir_name(key))
+                .join("index.html");
+            render_listing_page(&author_templates.author, path, placeholder_contents, ctx)
+        })
+        .collect::<OutputResult<Vec<_>>>()?;
+
+    log!(ok: "Generated {} author pages", author_pages.len());
+
+    Ok(author_pages)
+}
+
+pub fn author_url(key: &str) -> String {
+    format!("/authors/{}/", url_dir_name(key))
+}
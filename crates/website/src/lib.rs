@@ -1,35 +1,121 @@
 #![deny(clippy::unwrap_used)]
 
-use std::path::Path;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
 
 use articles::{ArticlePage, IndexPage};
 use loss72_platemaker_construct::{ConstructFile, Construction};
-use loss72_platemaker_core::fs::Directory;
+use loss72_platemaker_core::{
+    fs::{Directory, File},
+    log,
+    model::GenerationContext,
+};
+use loss72_platemaker_template::{Placeholder, Template, TemplateError};
+use loss72_platemaker_widgets::{
+    article_type::ArticleTypeDefinition,
+    messages::MessageCatalog,
+    user::{RESERVED_NAMES, UserWidget, UserWidgetDefinition},
+};
 
+mod ai_usage;
+mod article_types;
 mod articles;
+mod authors;
+mod relations;
+mod series;
+mod tags;
 
+pub use ai_usage::{AiUsageHtmlTemplates, generate_ai_usage_pages};
+pub use article_types::{ArticleTypes, TypeHtmlTemplates, generate_type_pages, type_url};
 pub use articles::{generate_article_html, generate_index_html};
+pub use authors::{
+    Author, AuthorHtmlTemplates, AuthorLink, Authors, author_url, generate_author_pages,
+};
+pub use relations::ArticleRelations;
+pub use series::{SeriesHtmlTemplates, generate_series_pages, series_url};
+pub use tags::{TagHtmlTemplates, generate_tag_pages, tag_url};
 
 #[derive(Debug, thiserror::Error)]
 pub enum WebsiteGenerationError {
     #[error("These placeholder is invalid: {}", .0.join(", "))]
     InvalidPlaceholder(Vec<String>),
 
+    #[error("Template {} is invalid: {reason}", .path.display())]
+    InvalidTemplate {
+        path: PathBuf,
+        reason: TemplateError,
+    },
+
     #[error("I/O Error: {0}")]
     IOError(#[from] std::io::Error),
+
+    #[error("authors.toml is invalid: {0}")]
+    InvalidAuthors(#[from] toml::de::Error),
+
+    #[error("messages.toml is invalid: {0}")]
+    InvalidMessages(toml::de::Error),
+
+    #[error("messages.toml has a message which is not a string: {0}")]
+    InvalidMessage(String),
+
+    #[error("Widget in the configuration is invalid: {0}")]
+    InvalidWidgetDefinition(String),
+
+    #[error("Article '{slug}' has invalid widgets:\n{}", .errors.join("\n"))]
+    InvalidWidget { slug: String, errors: Vec<String> },
+
+    #[error("Article '{slug}' refers to unknown authors: {}", .keys.join(", "))]
+    UnknownAuthors { slug: String, keys: Vec<String> },
+
+    #[error("AI usage report could not be serialized: {0}")]
+    InvalidReport(serde_json::Error),
+
+    #[error(
+        "Article '{slug}' has the article type '{name}', which is not declared in the configuration"
+    )]
+    UnknownArticleType { slug: String, name: String },
+
+    #[error("The default article type '{0}' is not declared in the configuration")]
+    UnknownDefaultArticleType(String),
+
+    #[error("The {kind} names {} share the page directory '{dir}'", .names.join(", "))]
+    ConflictingDirName {
+        kind: &'static str,
+        dir: String,
+        names: Vec<String>,
+    },
 }
 
 pub type OutputResult<T> = Result<T, WebsiteGenerationError>;
 
 #[derive(Debug)]
 pub struct WebPageHtmlTemplates {
-    pub article: String,
-    pub index: String,
+    pub article: Template,
+    pub index: Template,
     pub index_style: String,
-    pub index_list: String,
+    pub index_list: Template,
+    pub tags: Option<TagHtmlTemplates>,
+    pub series: Option<SeriesHtmlTemplates>,
+    pub author_pages: Option<AuthorHtmlTemplates>,
+    pub ai_usage: Option<AiUsageHtmlTemplates>,
+    pub types: Option<TypeHtmlTemplates>,
+    /// The registry in `authors.toml`, which is empty if the file does not exist.
+    pub authors: Authors,
+    pub article_types: ArticleTypes,
+    /// The built-in messages, overridden by `messages.toml` if it exists.
+    pub messages: MessageCatalog,
+    pub user_widgets: Vec<UserWidget>,
 }
 
-pub fn load_templates(template_dir: &Directory) -> OutputResult<WebPageHtmlTemplates> {
+pub fn load_templates(
+    template_dir: &Directory,
+    user_widgets: &BTreeMap<String, UserWidgetDefinition>,
+    article_types: &BTreeMap<String, ArticleTypeDefinition>,
+    default_article_type: &str,
+    ctx: &GenerationContext,
+) -> OutputResult<WebPageHtmlTemplates> {
     let [article, index, index_list] =
         template_dir.get_files(&[&"_article.html", &"_index.html", &"_index-list.html"])?;
     let [index_style] = template_dir
@@ -38,24 +124,288 @@ pub fn load_templates(template_dir: &Directory) -> OutputResult<WebPageHtmlTempl
         .get_files(&[&"index.css"])?;
 
     Ok(WebPageHtmlTemplates {
-        article: article.read_to_string()?,
-        index: index.read_to_string()?,
+        article: read_template(template_dir, &article)?,
+        index: read_template(template_dir, &index)?,
         index_style: index_style.read_to_string()?,
-        index_list: index_list.read_to_string()?,
+        index_list: read_template(template_dir, &index_list)?,
+        tags: load_tag_templates(template_dir)?,
+        series: load_series_templates(template_dir)?,
+        author_pages: load_author_templates(template_dir)?,
+        ai_usage: load_ai_usage_templates(template_dir)?,
+        types: load_type_templates(template_dir)?,
+        authors: load_authors(template_dir)?,
+        article_types: ArticleTypes::load(template_dir, article_types, default_article_type)?,
+        messages: load_messages(template_dir, ctx)?,
+        user_widgets: load_user_widgets(template_dir, user_widgets)?,
     })
 }
 
+fn load_tag_templates(template_dir: &Directory) -> OutputResult<Option<TagHtmlTemplates>> {
+    if !template_dir.path().join("_tag.html").exists() {
+        log!(step: "_tag.html is not found; tag pages will not be generated");
+        return Ok(None);
+    }
+
+    let [tag, tag_list, tags, tags_list] = template_dir.get_files(&[
+        &"_tag.html",
+        &"_tag-list.html",
+        &"_tags.html",
+        &"_tags-list.html",
+    ])?;
+
+    Ok(Some(TagHtmlTemplates {
+        tag: read_template(template_dir, &tag)?,
+        tag_list: read_template(template_dir, &tag_list)?,
+        tags: read_template(template_dir, &tags)?,
+        tags_list: read_template(template_dir, &tags_list)?,
+    }))
+}
+
+fn load_series_templates(template_dir: &Directory) -> OutputResult<Option<SeriesHtmlTemplates>> {
+    if !template_dir.path().join("_series.html").exists() {
+        log!(step: "_series.html is not found; series pages will not be generated");
+        return Ok(None);
+    }
+
+    let [series, series_list] = template_dir.get_files(&[&"_series.html", &"_series-list.html"])?;
+
+    Ok(Some(SeriesHtmlTemplates {
+        series: read_template(template_dir, &series)?,
+        series_list: read_template(template_dir, &series_list)?,
+    }))
+}
+
+fn load_type_templates(template_dir: &Directory) -> OutputResult<Option<TypeHtmlTemplates>> {
+    if !template_dir.path().join("_type.html").exists() {
+        log!(step: "_type.html is not found; article type pages will not be generated");
+        return Ok(None);
+    }
+
+    let [type_page] = template_dir.get_files(&[&"_type.html"])?;
+
+    Ok(Some(TypeHtmlTemplates {
+        type_page: read_template(template_dir, &type_page)?,
+    }))
+}
+
+fn load_ai_usage_templates(template_dir: &Directory) -> OutputResult<Option<AiUsageHtmlTemplates>> {
+    if !template_dir.path().join("_ai-usage.html").exists() {
+        log!(step: "_ai-usage.html is not found; AI usage report will not be generated");
+        return Ok(None);
+    }
+
+    let [ai_usage, ai_usage_list] =
+        template_dir.get_files(&[&"_ai-usage.html", &"_ai-usage-list.html"])?;
+
+    Ok(Some(AiUsageHtmlTemplates {
+        ai_usage: read_template(template_dir, &ai_usage)?,
+        ai_usage_list: read_template(template_dir, &ai_usage_list)?,
+    }))
+}
+
+fn load_author_templates(template_dir: &Directory) -> OutputResult<Option<AuthorHtmlTemplates>> {
+    if !template_dir.path().join("_author.html").exists() {
+        log!(step: "_author.html is not found; author pages will not be generated");
+        return Ok(None);
+    }
+
+    let [author, author_list] = template_dir.get_files(&[&"_author.html", &"_author-list.html"])?;
+
+    Ok(Some(AuthorHtmlTemplates {
+        author: read_template(template_dir, &author)?,
+        author_list: read_template(template_dir, &author_list)?,
+    }))
+}
+
+/// Reads the template and parses its blocks, so that the unbalanced ones are reported before generating anything.
+/// Partials in `${> path}` and layouts in `${extends path}` are read from `template_dir`.
+pub(crate) fn read_template(template_dir: &Directory, file: &File) -> OutputResult<Template> {
+    Placeholder::from_strs("${", "}", None)
+        .expect("Regex is validated to include the capture group")
+        .parse_template(&file.read_to_string()?, &mut |path| {
+            std::fs::read_to_string(template_dir.path().join(path))
+                .map_err(|error| error.to_string())
+        })
+        .map_err(|reason| WebsiteGenerationError::InvalidTemplate {
+            path: file.path().to_path_buf(),
+            reason,
+        })
+}
+
+/// Partials and layouts used by `templates`, relative to the template directory.
+/// The templates which do not exist are skipped.
+/// They are not pages on their own, but the templates using them have to be rebuilt when they change.
+pub fn find_template_dependencies(
+    template_dir: &Directory,
+    templates: impl IntoIterator<Item = PathBuf>,
+) -> OutputResult<BTreeSet<PathBuf>> {
+    let mut dependencies = BTreeSet::new();
+
+    for template in templates {
+        if !template_dir.path().join(&template).exists() {
+            continue;
+        }
+
+        let [file] = template_dir.get_files(&[&template])?;
+        dependencies.extend(
+            read_template(template_dir, &file)?
+                .dependencies()
+                .iter()
+                .map(PathBuf::from),
+        );
+    }
+
+    Ok(dependencies)
+}
+
+fn load_authors(template_dir: &Directory) -> OutputResult<Authors> {
+    if !template_dir.path().join("authors.toml").exists() {
+        return Ok(Authors::default());
+    }
+
+    let [authors] = template_dir.get_files(&[&"authors.toml"])?;
+
+    Ok(toml::from_str(&authors.read_to_string()?)?)
+}
+
+fn load_user_widgets(
+    template_dir: &Directory,
+    definitions: &BTreeMap<String, UserWidgetDefinition>,
+) -> OutputResult<Vec<UserWidget>> {
+    definitions
+        .iter()
+        .map(|(name, definition)| {
+            if RESERVED_NAMES.contains(&name.as_str()) {
+                return Err(WebsiteGenerationError::InvalidWidgetDefinition(format!(
+                    "'{name}' is the name of a built-in widget"
+                )));
+            }
+
+            let [html] = template_dir.get_files(&[&definition.template])?;
+            let style = match &definition.style {
+                Some(style) => {
+                    let [style] = template_dir.get_files(&[style])?;
+                    style.read_to_string()?
+                }
+                None => String::new(),
+            };
+
+            Ok(UserWidget {
+                name: name.clone(),
+                html: html.read_to_string()?,
+                style,
+                fields: definition.fields.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Reads the messages overriding the built-in ones from `messages.toml`, keyed by the locale.
+///
+/// ```toml
+/// [ja.footnote]
+/// title = "注"
+///
+/// [en]
+/// "footnote.title" = "Notes"
+/// ```
+fn load_messages(
+    template_dir: &Directory,
+    ctx: &GenerationContext,
+) -> OutputResult<MessageCatalog> {
+    let mut catalog = MessageCatalog::new(&ctx.default_language);
+
+    if !template_dir.path().join("messages.toml").exists() {
+        return Ok(catalog);
+    }
+
+    let [messages] = template_dir.get_files(&[&"messages.toml"])?;
+    let locales = toml::from_str::<toml::Table>(&messages.read_to_string()?)
+        .map_err(WebsiteGenerationError::InvalidMessages)?;
+
+    for (locale, messages) in &locales {
+        let mut entries = vec![];
+        flatten_messages(String::new(), messages, &mut entries)
+            .map_err(|key| WebsiteGenerationError::InvalidMessage(format!("{locale}.{key}")))?;
+
+        for (key, message) in entries {
+            catalog.insert(locale, key, message);
+        }
+    }
+
+    Ok(catalog)
+}
+
+/// Collects the strings in the nested tables with the dotted keys. Returns the key of the value if it is not a string.
+fn flatten_messages(
+    key: String,
+    value: &toml::Value,
+    entries: &mut Vec<(String, String)>,
+) -> Result<(), String> {
+    match value {
+        toml::Value::String(message) => entries.push((key, message.clone())),
+        toml::Value::Table(table) => {
+            for (child_key, value) in table {
+                let child_key = if key.is_empty() {
+                    child_key.clone()
+                } else {
+                    format!("{key}.{child_key}")
+                };
+                flatten_messages(child_key, value, entries)?;
+            }
+        }
+        _ => return Err(key),
+    }
+
+    Ok(())
+}
+
+/// Fails if distinct names of the tags, the series or the authors end up in the same page directory.
+pub(crate) fn check_url_dir_names<'a>(
+    kind: &'static str,
+    names: impl IntoIterator<Item = &'a str>,
+) -> OutputResult<()> {
+    let mut dirs = BTreeMap::<String, BTreeSet<&str>>::new();
+    for name in names {
+        dirs.entry(url_dir_name(name)).or_default().insert(name);
+    }
+
+    match dirs.into_iter().find(|(_, names)| names.len() > 1) {
+        Some((dir, names)) => Err(WebsiteGenerationError::ConflictingDirName {
+            kind,
+            dir,
+            names: names.into_iter().map(|name| format!("'{name}'")).collect(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Directory name for the tag, the series or the author, with characters that cannot be a part of the path replaced.
+pub(crate) fn url_dir_name(name: &str) -> String {
+    let name = name.trim();
+    if name.chars().all(|char| char == '.') {
+        return "-".repeat(name.len().max(1));
+    }
+
+    name.chars()
+        .map(|char| {
+            if char.is_whitespace() || matches!(char, '/' | '\\' | '?' | '#' | '%') {
+                '-'
+            } else {
+                char
+            }
+        })
+        .collect()
+}
+
+/// `pages` are the listing pages such as the index page and the tag pages, placed relative to the root.
 pub fn get_webpage_construction<'a>(
-    index: Option<&'a IndexPage>,
+    pages: &'a [IndexPage],
     articles: &'a [ArticlePage],
 ) -> Construction<'a> {
     Construction {
         dir: Path::new(""),
-        content: if let Some(index) = index {
-            vec![index.into()]
-        } else {
-            vec![]
-        },
+        content: pages.iter().map(ConstructFile::from).collect(),
         sub_dir: vec![Construction {
             dir: Path::new("articles"),
             content: articles.iter().map(ConstructFile::from).collect(),
@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+
+use loss72_platemaker_core::model::Article;
+
+/// Language of the article, where `None` is the default language.
+type Language<'a> = Option<&'a str>;
+
+/// Relations between the articles which cannot be known from a single article, such as the
+/// series it belongs to. This has to be built from all the articles before generating their pages.
+///
+/// Series and previous/next links are made among the articles in the same language.
+#[derive(Debug, Default)]
+pub struct ArticleRelations<'a> {
+    series: BTreeMap<(Language<'a>, &'a str), Vec<&'a Article>>,
+    /// Articles to be linked from the previous/next links, from the oldest one.
+    chronological: BTreeMap<Language<'a>, Vec<&'a Article>>,
+    /// All the articles sorted by the group and the slug, so that the translations are adjacent.
+    translations: Vec<&'a Article>,
+}
+
+impl<'a> ArticleRelations<'a> {
+    pub fn new(articles: impl IntoIterator<Item = &'a Article>) -> Self {
+        let mut series = BTreeMap::<_, Vec<&Article>>::new();
+        let mut chronological = BTreeMap::<_, Vec<&Article>>::new();
+        let mut translations = vec![];
+        for article in articles {
+            let lang = article.id.lang.as_deref();
+
+            chronological.entry(lang).or_default();
+            if !article.metadata.draft && !article.metadata.unlisted {
+                chronological.entry(lang).or_default().push(article);
+            }
+
+            if let Some(name) = &article.metadata.series {
+                series
+                    .entry((lang, name.as_str()))
+                    .or_default()
+                    .push(article);
+            }
+
+            translations.push(article);
+        }
+
+        for parts in series.values_mut() {
+            parts.sort_by(|left, right| {
+                let key = |article: &Article| {
+                    (
+                        article.metadata.series_order.is_none(),
+                        article.metadata.series_order,
+                    )
+                };
+
+                key(left)
+                    .cmp(&key(right))
+                    .then_with(|| left.id.cmp(&right.id))
+            });
+            parts.dedup_by(|left, right| left.id == right.id);
+        }
+
+        for articles in chronological.values_mut() {
+            articles.sort_by(|left, right| left.id.cmp(&right.id));
+            articles.dedup_by(|left, right| left.id == right.id);
+        }
+
+        translations.sort_by(|left, right| translation_key(left).cmp(&translation_key(right)));
+        translations.dedup_by(|left, right| left.id == right.id);
+
+        Self {
+            series,
+            chronological,
+            translations,
+        }
+    }
+
+    /// Languages of the articles, starting from the default language (`None`) if there are such articles.
+    pub fn languages(&self) -> impl Iterator<Item = Language<'a>> + '_ {
+        self.chronological.keys().copied()
+    }
+
+    /// The article published right before `article` in the same language, skipping drafts and unlisted ones.
+    ///
+    /// `article` itself does not have to be listed.
+    pub fn previous(&self, article: &Article) -> Option<&'a Article> {
+        let listed = self.chronological.get(&article.id.lang.as_deref())?;
+        let index = listed.partition_point(|listed| listed.id < article.id);
+
+        index
+            .checked_sub(1)
+            .and_then(|index| listed.get(index))
+            .copied()
+    }
+
+    /// The article published right after `article` in the same language, skipping drafts and unlisted ones.
+    ///
+    /// `article` itself does not have to be listed.
+    pub fn next(&self, article: &Article) -> Option<&'a Article> {
+        let listed = self.chronological.get(&article.id.lang.as_deref())?;
+        let index = listed.partition_point(|listed| listed.id <= article.id);
+
+        listed.get(index).copied()
+    }
+
+    /// All the translations of `article` including itself, starting from the default language.
+    pub fn translations(&self, article: &Article) -> &[&'a Article] {
+        let (group, slug, _) = translation_key(article);
+        let start = self
+            .translations
+            .partition_point(|translation| translation_key(translation) < (group, slug, None));
+        let length = self.translations[start..].partition_point(|translation| {
+            translation.id.group == group && translation.id.slug == slug
+        });
+
+        &self.translations[start..start + length]
+    }
+
+    /// Returns `true` if the page of `other` shows anything of `article`,
+    /// so that it has to be regenerated when `article` is changed.
+    pub fn is_referred_from(&self, article: &Article, other: &Article) -> bool {
+        let is_neighbour = |neighbour: Option<&Article>| {
+            neighbour.is_some_and(|neighbour| neighbour.id == other.id)
+        };
+
+        other.id.is_translation_of(&article.id)
+            || (other.id.lang == article.id.lang
+                && other.metadata.series.is_some()
+                && other.metadata.series == article.metadata.series)
+            || is_neighbour(self.previous(article))
+            || is_neighbour(self.next(article))
+    }
+
+    /// Names of the series in the default language, in alphabetical order.
+    pub fn series_names(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.series
+            .keys()
+            .filter(|(lang, _)| lang.is_none())
+            .map(|(_, name)| *name)
+    }
+
+    /// Parts of the series in reading order.
+    pub fn series(&self, lang: Language<'_>, name: &str) -> &[&'a Article] {
+        self.series
+            .iter()
+            .find(|((series_lang, series_name), _)| *series_lang == lang && *series_name == name)
+            .map(|(_, parts)| parts.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+fn translation_key(article: &Article) -> (&str, &str, Option<&str>) {
+    (
+        article.id.group.as_str(),
+        article.id.slug.as_str(),
+        article.id.lang.as_deref(),
+    )
+}
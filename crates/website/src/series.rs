@@ -0,0 +1,129 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use loss72_platemaker_core::{
+    log,
+    model::{Article, GenerationContext},
+};
+use loss72_platemaker_template::Template;
+use loss72_platemaker_widgets::series::{Series, SeriesPart};
+
+use crate::{
+    OutputResult, WebPageHtmlTemplates,
+    articles::{ArticlePage, IndexPage, article_url, render_article_list, render_listing_page},
+    check_url_dir_names,
+    relations::ArticleRelations,
+    url_dir_name,
+};
+
+#[derive(Debug)]
+pub struct SeriesHtmlTemplates {
+    pub series: Template,
+    pub series_list: Template,
+}
+
+/// Generates `/series/<series>/index.html` listing the parts of each series in reading order.
+/// Only the series in the default language get the pages.
+///
+/// Returns nothing if the series templates are not provided.
+pub fn generate_series_pages(
+    html_templates: &WebPageHtmlTemplates,
+    relations: &ArticleRelations,
+    articles: &[ArticlePage],
+    ctx: &GenerationContext,
+) -> OutputResult<Vec<IndexPage>> {
+    let Some(series_templates) = &html_templates.series else {
+        return Ok(vec![]);
+    };
+
+    log!(section: "Generating HTML for series pages");
+
+    check_url_dir_names("series", relations.series_names())?;
+
+    let series_pages = relations
+        .series_names()
+        .map(|name| {
+            log!(step: "Generating HTML for series '{}'", name);
+
+            let pages = relations
+                .series(None, name)
+                .iter()
+                .filter_map(|part| articles.iter().find(|page| page.article.id == part.id))
+                .collect::<Vec<_>>();
+
+            let placeholder_contents = HashMap::from([
+                ("series_name", name.to_string()),
+                ("count", pages.len().to_string()),
+                (
+                    "articles",
+                    render_article_list(
+                        &series_templates.series_list,
+                        pages.iter().copied(),
+                        html_templates,
+                        ctx,
+                    )?,
+                ),
+                ("style", html_templates.index_style.clone()),
+            ]);
+            let path = PathBuf::from("series")
+                .join(url_dir_name(name))
+                .join("index.html");
+            render_listing_page(&series_templates.series, path, placeholder_contents, ctx)
+        })
+        .collect::<OutputResult<Vec<_>>>()?;
+
+    log!(ok: "Generated {} series pages", series_pages.len());
+
+    Ok(series_pages)
+}
+
+pub fn series_url(name: &str) -> String {
+    format!("/series/{}/", url_dir_name(name))
+}
+
+/// Builds the series widget and the links to the previous and next parts.
+/// Everything is empty if the article does not belong to any series.
+pub(crate) fn series_navigation(
+    relations: &ArticleRelations,
+    article: &Article,
+) -> (Series, [(&'static str, String); 2]) {
+    let Some(name) = &article.metadata.series else {
+        return (
+            Series::default(),
+            [
+                ("series_prev_url", String::new()),
+                ("series_next_url", String::new()),
+            ],
+        );
+    };
+
+    let parts = relations.series(article.id.lang.as_deref(), name);
+    let position = parts.iter().position(|part| part.id == article.id);
+    let neighbour_url = |offset: isize| {
+        position
+            .and_then(|position| position.checked_add_signed(offset))
+            .and_then(|position| parts.get(position))
+            .map(|part| article_url(part))
+            .unwrap_or_default()
+    };
+
+    let widget = Series {
+        name: name.clone(),
+        url: series_url(name),
+        parts: parts
+            .iter()
+            .map(|part| SeriesPart {
+                title: part.metadata.title.clone(),
+                url: article_url(part),
+                current: part.id == article.id,
+            })
+            .collect(),
+    };
+
+    (
+        widget,
+        [
+            ("series_prev_url", neighbour_url(-1)),
+            ("series_next_url", neighbour_url(1)),
+        ],
+    )
+}
@@ -0,0 +1,134 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use loss72_platemaker_core::{log, model::GenerationContext};
+use loss72_platemaker_template::Template;
+
+use crate::{
+    OutputResult, WebPageHtmlTemplates, WebsiteGenerationError,
+    articles::{ArticlePage, IndexPage, render_article_list, render_listing_page},
+    check_url_dir_names, url_dir_name,
+};
+
+#[derive(Debug)]
+pub struct TagHtmlTemplates {
+    pub tag: Template,
+    pub tag_list: Template,
+    pub tags: Template,
+    pub tags_list: Template,
+}
+
+/// Generates `/tags/<tag>/index.html` for each tag, and `/tags/index.html` listing all of them.
+/// Only the articles in the default language are listed.
+///
+/// Returns nothing if the tag templates are not provided.
+pub fn generate_tag_pages(
+    html_templates: &WebPageHtmlTemplates,
+    articles: &[ArticlePage],
+    ctx: &GenerationContext,
+) -> OutputResult<Vec<IndexPage>> {
+    let Some(tag_templates) = &html_templates.tags else {
+        return Ok(vec![]);
+    };
+
+    log!(section: "Generating HTML for tag pages");
+
+    let mut tagged_articles = BTreeMap::<&str, Vec<&ArticlePage>>::new();
+    for page in articles
+        .iter()
+        .filter(|page| !page.article.metadata.unlisted && page.article.id.lang.is_none())
+    {
+        for tag in &page.article.metadata.tags {
+            let pages = tagged_articles.entry(tag.as_str()).or_default();
+            if !pages
+                .iter()
+                .any(|tagged| tagged.article.id == page.article.id)
+            {
+                pages.push(page);
+            }
+        }
+    }
+
+    check_url_dir_names("tag", tagged_articles.keys().copied())?;
+
+    let mut tag_pages = tagged_articles
+        .iter()
+        .map(|(tag, pages)| {
+            log!(step: "Generating HTML for tag '{}'", tag);
+
+            let placeholder_contents = HashMap::from([
+                ("tag", tag.to_string()),
+                ("count", pages.len().to_string()),
+                (
+                    "articles",
+                    render_article_list(
+                        &tag_templates.tag_list,
+                        pages.iter().copied(),
+                        html_templates,
+                        ctx,
+                    )?,
+                ),
+                ("style", html_templates.index_style.clone()),
+            ]);
+            let path = PathBuf::from("tags")
+                .join(url_dir_name(tag))
+                .join("index.html");
+            render_listing_page(&tag_templates.tag, path, placeholder_contents, ctx)
+        })
+        .collect::<OutputResult<Vec<_>>>()?;
+
+    let tag_list = tagged_articles
+        .iter()
+        .map(|(tag, pages)| {
+            let placeholder_contents = HashMap::from([
+                ("tag", tag.to_string()),
+                ("url", tag_url(tag)),
+                ("count", pages.len().to_string()),
+            ]);
+
+            tag_templates
+                .tags_list
+                .render(&placeholder_contents)
+                .map_err(WebsiteGenerationError::InvalidPlaceholder)
+        })
+        .collect::<OutputResult<String>>()?;
+
+    let placeholder_contents = HashMap::from([
+        ("tags", tag_list),
+        ("count", tagged_articles.len().to_string()),
+        ("style", html_templates.index_style.clone()),
+    ]);
+    tag_pages.push(render_listing_page(
+        &tag_templates.tags,
+        PathBuf::from("tags").join("index.html"),
+        placeholder_contents,
+        ctx,
+    )?);
+
+    log!(ok: "Generated {} tag pages", tagged_articles.len());
+
+    Ok(tag_pages)
+}
+
+/// Renders the links to the tag pages, used for `${tags}` in article and index list templates.
+pub fn render_tag_links(tags: &[String]) -> String {
+    if tags.is_empty() {
+        return String::new();
+    }
+
+    format!(
+        r#"<ul class="tags">{}</ul>"#,
+        tags.iter()
+            .map(|tag| format!(
+                r#"<li class="tag"><a href="{}">{tag}</a></li>"#,
+                tag_url(tag)
+            ))
+            .collect::<String>()
+    )
+}
+
+pub fn tag_url(tag: &str) -> String {
+    format!("/tags/{}/", url_dir_name(tag))
+}
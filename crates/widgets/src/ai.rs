@@ -1,6 +1,6 @@
 use serde::Deserialize;
 
-use crate::Widget;
+use crate::{Widget, messages::Messages};
 
 #[derive(Clone, Default, Debug, Deserialize, PartialEq, Eq)]
 pub enum AiUsage {
@@ -15,28 +15,48 @@ pub enum AiUsage {
 }
 
 impl AiUsage {
-    pub fn description(&self) -> (&str, &str) {
+    /// All the levels, from the least use of AI.
+    pub const ALL: [AiUsage; 7] = [
+        AiUsage::Unused,
+        AiUsage::Review,
+        AiUsage::NonText,
+        AiUsage::ResearchSupport,
+        AiUsage::Research,
+        AiUsage::ArticleOutlining,
+        AiUsage::MainText,
+    ];
+
+    /// Identifier of the level used in the URLs and the JSON, such as `non_text`.
+    pub fn name(&self) -> &'static str {
         match self {
-            AiUsage::Unused => ("AI not used", "この記事では AI は使っていません"),
-            AiUsage::Review => ("AI used for review", "この記事は推敲に AI を使っています"),
-            AiUsage::NonText => (
-                "AI generated non-text contents",
-                "テキスト以外のコンテンツで AI を使っています",
-            ),
+            AiUsage::Unused => "unused",
+            AiUsage::Review => "review",
+            AiUsage::NonText => "non_text",
+            AiUsage::ResearchSupport => "research_support",
+            AiUsage::Research => "research",
+            AiUsage::ArticleOutlining => "article_outlining",
+            AiUsage::MainText => "main_text",
+        }
+    }
+
+    pub fn description<'m>(&self, messages: &Messages<'m>) -> (&'m str, &'m str) {
+        let (brief, description) = match self {
+            AiUsage::Unused => ("ai.unused.brief", "ai.unused.description"),
+            AiUsage::Review => ("ai.review.brief", "ai.review.description"),
+            AiUsage::NonText => ("ai.non_text.brief", "ai.non_text.description"),
             AiUsage::ResearchSupport => (
-                "AI supported researching for this",
-                "この記事を書くにあたって、AI と協力して調査しました",
-            ),
-            AiUsage::Research => (
-                "AI researched for this",
-                "この記事を書くにあたって、AI に調査してもらいました",
+                "ai.research_support.brief",
+                "ai.research_support.description",
             ),
+            AiUsage::Research => ("ai.research.brief", "ai.research.description"),
             AiUsage::ArticleOutlining => (
-                "AI generated the outline",
-                "記事の構成作成に AI を使っています",
+                "ai.article_outlining.brief",
+                "ai.article_outlining.description",
             ),
-            AiUsage::MainText => ("AI generated the outline", "本文作成に AI を使っています"),
-        }
+            AiUsage::MainText => ("ai.main_text.brief", "ai.main_text.description"),
+        };
+
+        (messages.get(brief), messages.get(description))
     }
 
     pub fn heavy_use(&self) -> bool {
@@ -55,7 +75,7 @@ impl AiUsage {
 impl Widget for AiUsage {
     const TAG: &'static str = "ai";
 
-    fn build(&self) -> String {
+    fn build(&self, messages: &Messages) -> String {
         if self == &Self::Unused {
             return String::new();
         }
@@ -65,7 +85,7 @@ impl Widget for AiUsage {
         } else {
             ""
         };
-        let (brief, description) = self.description();
+        let (brief, description) = self.description(messages);
 
         format!(
             r#"
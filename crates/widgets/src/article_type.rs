@@ -1,41 +1,106 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
 use serde::Deserialize;
 
-use crate::Widget;
+use crate::{Widget, messages::Messages};
+
+/// Name of the article type given as `widgets.article_type`, which has to be declared in the configuration.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ArticleType(pub String);
+
+/// Type of the articles without `widgets.article_type`, unless the configuration names another one.
+pub fn default_article_type() -> String {
+    "Activity".to_string()
+}
+
+/// Article type declared in the configuration.
+///
+/// ```toml
+/// [article_types.Talk]
+/// description = "Talk"
+/// class = "article-talk"
+/// list_template = "_index-list-talk.html"
+/// listing_page = true
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ArticleTypeDefinition {
+    /// Shown as the type of the article. The built-in types use the messages if this is not given.
+    pub description: Option<String>,
+    /// CSS class of the article, `article-<lowercased name>` with the whitespace and the symbols replaced with `-`
+    /// by default.
+    pub class: Option<String>,
+    /// Used instead of `_index-list.html` for the articles of this type, relative to the template directory.
+    pub list_template: Option<PathBuf>,
+    /// Generates `/types/<name>/index.html` from `_type.html`.
+    #[serde(default)]
+    pub listing_page: bool,
+    /// Shows the staleness notice on the old articles.
+    #[serde(default)]
+    pub staleness_notice: bool,
+}
 
-#[derive(Clone, Default, Debug, Deserialize)]
-pub enum ArticleType {
-    #[default]
-    Activity,
-    Research,
+/// Types available when the configuration declares none.
+pub fn builtin_article_types() -> BTreeMap<String, ArticleTypeDefinition> {
+    BTreeMap::from([
+        ("Activity".to_string(), ArticleTypeDefinition::default()),
+        (
+            "Research".to_string(),
+            ArticleTypeDefinition {
+                staleness_notice: true,
+                ..Default::default()
+            },
+        ),
+    ])
 }
 
-impl ArticleType {
-    pub fn description(&self) -> &str {
-        match self {
-            Self::Activity => "活動記録",
-            Self::Research => "学習記録",
+impl ArticleTypeDefinition {
+    pub fn description<'m>(&'m self, name: &'m str, messages: &Messages<'m>) -> &'m str {
+        match (&self.description, name) {
+            (Some(description), _) => description,
+            (None, "Activity") => messages.get("article_type.activity"),
+            (None, "Research") => messages.get("article_type.research"),
+            (None, name) => name,
         }
     }
 
-    pub fn class_name(&self) -> &str {
-        match self {
-            Self::Activity => "article-activity",
-            Self::Research => "article-research",
-        }
+    pub fn class_name(&self, name: &str) -> String {
+        self.class.clone().unwrap_or_else(|| {
+            let slug = name
+                .to_lowercase()
+                .chars()
+                .map(|char| {
+                    if char.is_alphanumeric() || matches!(char, '-' | '_') {
+                        char
+                    } else {
+                        '-'
+                    }
+                })
+                .collect::<String>();
+
+            format!("article-{slug}")
+        })
     }
 }
 
-impl Widget for ArticleType {
+/// Heading showing the type of the article.
+#[derive(Clone, Debug)]
+pub struct ArticleTypeHeading<'d> {
+    pub name: &'d str,
+    pub definition: &'d ArticleTypeDefinition,
+}
+
+impl Widget for ArticleTypeHeading<'_> {
     const TAG: &'static str = "type";
 
-    fn build(&self) -> String {
+    fn build(&self, messages: &Messages) -> String {
         format!(
             r#"
             <h2 class="article-type">
                 {}
             </h2>
         "#,
-            self.description(),
+            self.definition.description(self.name, messages),
         )
     }
 
@@ -43,3 +108,26 @@ impl Widget for ArticleType {
         ""
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ArticleTypeDefinition;
+
+    #[test]
+    fn makes_class_name_from_type_name() {
+        let definition = ArticleTypeDefinition::default();
+
+        assert_eq!(definition.class_name("Activity"), "article-activity");
+        assert_eq!(
+            definition.class_name("Release Note"),
+            "article-release-note"
+        );
+        assert_eq!(definition.class_name("Q&A"), "article-q-a");
+
+        let definition = ArticleTypeDefinition {
+            class: Some("talk".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(definition.class_name("Release Note"), "talk");
+    }
+}
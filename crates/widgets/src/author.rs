@@ -0,0 +1,72 @@
+use crate::{Widget, messages::Messages};
+
+/// Byline listing the authors of the article.
+#[derive(Clone, Default, Debug)]
+pub struct Byline(pub Vec<BylineAuthor>);
+
+#[derive(Clone, Debug)]
+pub struct BylineAuthor {
+    pub name: String,
+    /// Link to the author page, if the site has one.
+    pub url: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+impl Widget for Byline {
+    const TAG: &'static str = "authors";
+
+    fn build(&self, _messages: &Messages) -> String {
+        if self.0.is_empty() {
+            return String::new();
+        }
+
+        format!(
+            r#"<ul class="byline">{}</ul>"#,
+            self.0
+                .iter()
+                .map(|author| author.to_html())
+                .collect::<String>(),
+        )
+    }
+
+    fn style(&self) -> &'static str {
+        r#"
+            .byline {
+                display: flex;
+                flex-wrap: wrap;
+                gap: 1em;
+
+                .author, .author a {
+                    display: inline-flex;
+                    align-items: center;
+                    gap: 0.5em;
+                }
+
+                .avatar {
+                    width: 2em;
+                    height: 2em;
+                    border-radius: 50%;
+                    object-fit: cover;
+                }
+            }
+        "#
+    }
+}
+
+impl BylineAuthor {
+    pub fn to_html(&self) -> String {
+        let name = &self.name;
+        let avatar = self
+            .avatar_url
+            .as_ref()
+            .map(|avatar_url| format!(r#"<img class="avatar" src="{avatar_url}" alt="">"#))
+            .unwrap_or_default();
+
+        match &self.url {
+            Some(url) => format!(
+                r#"<li class="author"><a href="{url}" rel="author">{avatar}<span class="name">{name}</span></a></li>"#
+            ),
+            None => format!(r#"<li class="author">{avatar}<span class="name">{name}</span></li>"#),
+        }
+    }
+}
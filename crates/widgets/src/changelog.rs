@@ -0,0 +1,69 @@
+use crate::{GroundingWidget, messages::Messages};
+
+#[derive(Clone, Default, Debug)]
+pub struct Changelog(pub Vec<Revision>);
+
+#[derive(Clone, Debug)]
+pub struct Revision {
+    pub date: String,
+    pub summary: String,
+}
+
+impl GroundingWidget for Changelog {
+    const TAG: &'static str = "changelog";
+
+    fn title<'m>(&self, messages: &Messages<'m>) -> (&'m str, &'m str) {
+        (
+            messages.get("changelog.title"),
+            messages.get("changelog.subtitle"),
+        )
+    }
+
+    fn build_content(&self, _messages: &Messages) -> Option<String> {
+        if self.0.is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            r#"<ol class="changelog-list">{}</ol>"#,
+            self.0
+                .iter()
+                .map(|revision| revision.to_html())
+                .collect::<String>(),
+        ))
+    }
+
+    fn content_style(&self) -> &'static str {
+        r#"
+            .changelog-list {
+                display: flex;
+                flex-direction: column;
+                gap: 0.5em;
+            }
+
+            .revision {
+                .date {
+                    color: var(--typed-primary);
+                    font-variant-numeric: tabular-nums;
+                    margin-right: 1em;
+                }
+            }
+        "#
+    }
+}
+
+impl Revision {
+    pub fn to_html(&self) -> String {
+        let date = &self.date;
+        let summary = &self.summary;
+
+        format!(
+            r#"
+            <li class="revision">
+                <time class="date" datetime="{date}">{date}</time>
+                <span class="summary">{summary}</span>
+            </li>
+        "#
+        )
+    }
+}
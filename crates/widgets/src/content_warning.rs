@@ -0,0 +1,55 @@
+use serde::Deserialize;
+
+use crate::{Widget, messages::Messages};
+
+/// Notice shown at the top of the article, e.g. `{ kind = "spoiler", note = "..." }`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ContentWarning {
+    pub kind: String,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+impl ContentWarning {
+    pub const FIELDS: &[&str] = &["kind", "note"];
+}
+
+impl Widget for ContentWarning {
+    const TAG: &'static str = "content_warning";
+
+    fn build(&self, messages: &Messages) -> String {
+        let title = messages.get("content_warning.title");
+        let kind = &self.kind;
+        let note = self
+            .note
+            .as_ref()
+            .map(|note| format!(r#"<p class="note">{note}</p>"#))
+            .unwrap_or_default();
+
+        format!(
+            r#"
+            <aside class="content-warning" role="note">
+                <p class="title">{title}: <span class="kind">{kind}</span></p>
+                {note}
+            </aside>
+        "#
+        )
+    }
+
+    fn style(&self) -> &'static str {
+        r#"
+            .content-warning {
+                border-left: 4px solid var(--typed-primary);
+                padding: 6px 12px;
+
+                .title {
+                    font-weight: bold;
+                }
+
+                .kind {
+                    color: var(--typed-primary);
+                }
+            }
+        "#
+    }
+}
@@ -1,36 +1,48 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
 
+use messages::Messages;
 use serde::Deserialize;
 
 pub mod ai;
 pub mod article_type;
+pub mod author;
+pub mod changelog;
+pub mod content_warning;
+pub mod messages;
+pub mod series;
 pub mod sources;
+pub mod spoiler;
+pub mod staleness;
+pub mod user;
 
 pub trait Widget {
     const TAG: &'static str;
 
-    fn build(&self) -> String;
+    fn build(&self, messages: &Messages) -> String;
     fn style(&self) -> &'static str;
 
-    fn render_widget(&self) -> (&'static str, String) {
-        (Self::TAG, self.build())
+    fn render_widget(&self, messages: &Messages) -> (&'static str, String) {
+        (Self::TAG, self.build(messages))
     }
 }
 
 pub trait GroundingWidget {
     const TAG: &'static str;
 
-    fn title(&self) -> (&'static str, &'static str);
-    fn build_content(&self) -> Option<String>;
+    fn title<'m>(&self, messages: &Messages<'m>) -> (&'m str, &'m str);
+    fn build_content(&self, messages: &Messages) -> Option<String>;
     fn content_style(&self) -> &'static str;
 }
 
 impl<T: GroundingWidget> Widget for T {
     const TAG: &'static str = T::TAG;
 
-    fn build(&self) -> String {
-        let (first_title, second_title) = self.title();
-        let Some(content) = self.build_content() else {
+    fn build(&self, messages: &Messages) -> String {
+        let (first_title, second_title) = self.title(messages);
+        let Some(content) = self.build_content(messages) else {
             return String::new();
         };
 
@@ -59,19 +71,47 @@ pub struct Widgets {
     #[serde(default)]
     pub ai: ai::AiUsage,
 
+    /// The default type of the configuration is used if `None`.
     #[serde(default)]
-    pub article_type: article_type::ArticleType,
+    pub article_type: Option<article_type::ArticleType>,
 
     #[serde(default)]
     pub sources: sources::Sources,
+
+    /// BibTeX file in the article group, whose entries are added to `sources`.
+    #[serde(default)]
+    pub bibliography: Option<PathBuf>,
+
+    #[serde(default)]
+    pub content_warning: Option<content_warning::ContentWarning>,
+
+    /// Fields of the user widgets declared in the configuration, keyed by the name of the widget.
+    /// Any value is accepted here so that the unknown keys can be reported instead of failing the parse.
+    #[serde(flatten)]
+    pub user: BTreeMap<String, toml::Value>,
 }
 
 impl Widgets {
-    pub fn render_to_placeholder_content(&self) -> HashMap<&'static str, String> {
+    /// Keys of the built-in widgets. The other keys are for the user widgets.
+    pub const FIELDS: &[&str] = &[
+        "ai",
+        "article_type",
+        "sources",
+        "bibliography",
+        "content_warning",
+    ];
+
+    pub fn render_to_placeholder_content(
+        &self,
+        messages: &Messages,
+    ) -> HashMap<&'static str, String> {
         HashMap::from([
-            self.ai.render_widget(),
-            self.article_type.render_widget(),
-            self.sources.render_widget(),
+            self.ai.render_widget(messages),
+            self.sources.render_widget(messages),
+            self.content_warning
+                .as_ref()
+                .map(|content_warning| content_warning.render_widget(messages))
+                .unwrap_or((content_warning::ContentWarning::TAG, String::new())),
             ("widget_styles", self.concatenate_styles()),
         ])
     }
@@ -79,8 +119,12 @@ impl Widgets {
     pub fn concatenate_styles(&self) -> String {
         let mut style = String::new();
         style.push_str(self.ai.style());
-        style.push_str(self.article_type.style());
         style.push_str(self.sources.style());
+        if let Some(content_warning) = &self.content_warning {
+            style.push_str(content_warning.style());
+        }
+        // Spoilers can be anywhere in the body
+        style.push_str(spoiler::Spoiler::default().style());
 
         style
     }
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+/// Built-in messages in Japanese. This is the last fallback, so every key has to be here.
+const JA: &[(&str, &str)] = &[
+    ("footnote.title", "脚注"),
+    ("footnote.back", "戻る"),
+    ("article_type.activity", "活動記録"),
+    ("article_type.research", "学習記録"),
+    ("ai.unused.brief", "AI not used"),
+    ("ai.unused.description", "この記事では AI は使っていません"),
+    ("ai.review.brief", "AI used for review"),
+    (
+        "ai.review.description",
+        "この記事は推敲に AI を使っています",
+    ),
+    ("ai.non_text.brief", "AI generated non-text contents"),
+    (
+        "ai.non_text.description",
+        "テキスト以外のコンテンツで AI を使っています",
+    ),
+    (
+        "ai.research_support.brief",
+        "AI supported researching for this",
+    ),
+    (
+        "ai.research_support.description",
+        "この記事を書くにあたって、AI と協力して調査しました",
+    ),
+    ("ai.research.brief", "AI researched for this"),
+    (
+        "ai.research.description",
+        "この記事を書くにあたって、AI に調査してもらいました",
+    ),
+    ("ai.article_outlining.brief", "AI generated the outline"),
+    (
+        "ai.article_outlining.description",
+        "記事の構成作成に AI を使っています",
+    ),
+    ("ai.main_text.brief", "AI generated the outline"),
+    ("ai.main_text.description", "本文作成に AI を使っています"),
+    ("sources.title", "ARTICLE SOURCES"),
+    ("sources.subtitle", "この記事の参考文献"),
+    ("sources.accessed", "閲覧日"),
+    ("sources.archive", "アーカイブ"),
+    ("changelog.title", "CHANGELOG"),
+    ("changelog.subtitle", "この記事の更新履歴"),
+    ("series.title", "SERIES"),
+    ("series.subtitle", "この記事を含む連載"),
+    ("ai_usage.period", "期間"),
+    ("content_warning.title", "閲覧注意"),
+    ("spoiler.summary", "ネタバレを表示"),
+    ("draft.banner", "下書き"),
+    (
+        "staleness.notice",
+        "この記事は {years} 年以上前のものです。内容が古くなっている可能性があります。",
+    ),
+    (
+        "staleness.notice_months",
+        "この記事は {months} か月以上前のものです。内容が古くなっている可能性があります。",
+    ),
+    (
+        "staleness.notice_days",
+        "この記事は {days} 日前のものです。内容が古くなっている可能性があります。",
+    ),
+];
+
+const EN: &[(&str, &str)] = &[
+    ("footnote.title", "Footnotes"),
+    ("footnote.back", "Back"),
+    ("article_type.activity", "Activity log"),
+    ("article_type.research", "Study notes"),
+    ("ai.unused.brief", "AI not used"),
+    ("ai.unused.description", "No AI was used for this article"),
+    ("ai.review.brief", "AI used for review"),
+    (
+        "ai.review.description",
+        "AI was used for proofreading this article",
+    ),
+    ("ai.non_text.brief", "AI generated non-text contents"),
+    (
+        "ai.non_text.description",
+        "AI was used for the contents other than the text",
+    ),
+    (
+        "ai.research_support.brief",
+        "AI supported researching for this",
+    ),
+    (
+        "ai.research_support.description",
+        "The research for this article was done together with AI",
+    ),
+    ("ai.research.brief", "AI researched for this"),
+    (
+        "ai.research.description",
+        "The research for this article was done by AI",
+    ),
+    ("ai.article_outlining.brief", "AI generated the outline"),
+    (
+        "ai.article_outlining.description",
+        "AI was used for outlining this article",
+    ),
+    ("ai.main_text.brief", "AI generated the main text"),
+    (
+        "ai.main_text.description",
+        "AI was used for writing the main text",
+    ),
+    ("sources.title", "ARTICLE SOURCES"),
+    ("sources.subtitle", "Sources of this article"),
+    ("sources.accessed", "Accessed"),
+    ("sources.archive", "Archived copy"),
+    ("changelog.title", "CHANGELOG"),
+    ("changelog.subtitle", "Revisions of this article"),
+    ("series.title", "SERIES"),
+    ("series.subtitle", "Series including this article"),
+    ("ai_usage.period", "Period"),
+    ("content_warning.title", "Content warning"),
+    ("spoiler.summary", "Show spoiler"),
+    ("draft.banner", "DRAFT"),
+    (
+        "staleness.notice",
+        "This article is over {years} year(s) old. The contents may be out of date.",
+    ),
+    (
+        "staleness.notice_months",
+        "This article is over {months} month(s) old. The contents may be out of date.",
+    ),
+    (
+        "staleness.notice_days",
+        "This article is {days} day(s) old. The contents may be out of date.",
+    ),
+];
+
+fn builtin(locale: &str) -> &'static [(&'static str, &'static str)] {
+    match locale {
+        "ja" => JA,
+        "en" => EN,
+        _ => &[],
+    }
+}
+
+/// Messages shown by the widgets and the parser, keyed by locale.
+///
+/// A message is looked up from the overrides and the built-in ones in the requested locale,
+/// then in the default locale, and finally in the built-in Japanese ones.
+#[derive(Clone, Debug)]
+pub struct MessageCatalog {
+    default_locale: String,
+    overrides: HashMap<String, HashMap<String, String>>,
+}
+
+impl Default for MessageCatalog {
+    fn default() -> Self {
+        Self::new("ja")
+    }
+}
+
+impl MessageCatalog {
+    pub fn new(default_locale: &str) -> Self {
+        Self {
+            default_locale: default_locale.to_string(),
+            overrides: HashMap::new(),
+        }
+    }
+
+    pub fn default_locale(&self) -> &str {
+        &self.default_locale
+    }
+
+    /// Replaces the message for `key` in `locale`.
+    pub fn insert(&mut self, locale: &str, key: String, message: String) {
+        self.overrides
+            .entry(locale.to_string())
+            .or_default()
+            .insert(key, message);
+    }
+
+    /// Messages in `locale`, or in the default locale if it is `None`.
+    pub fn messages(&self, locale: Option<&str>) -> Messages<'_> {
+        Messages {
+            catalog: self,
+            locale: locale.unwrap_or(&self.default_locale).to_string(),
+        }
+    }
+
+    fn lookup(&self, locale: &str, key: &str) -> Option<&str> {
+        self.overrides
+            .get(locale)
+            .and_then(|messages| messages.get(key))
+            .map(String::as_str)
+            .or_else(|| {
+                builtin(locale)
+                    .iter()
+                    .find(|(builtin_key, _)| *builtin_key == key)
+                    .map(|(_, message)| *message)
+            })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Messages<'c> {
+    catalog: &'c MessageCatalog,
+    locale: String,
+}
+
+impl<'c> Messages<'c> {
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Returns the key itself if no message is found.
+    pub fn get(&self, key: &'static str) -> &'c str {
+        self.catalog
+            .lookup(&self.locale, key)
+            .or_else(|| self.catalog.lookup(&self.catalog.default_locale, key))
+            .or_else(|| self.catalog.lookup("ja", key))
+            .unwrap_or(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EN, JA, MessageCatalog};
+
+    #[test]
+    fn builtin_locales_have_same_keys() {
+        let keys = |messages: &[(&'static str, &'static str)]| {
+            messages.iter().map(|(key, _)| *key).collect::<Vec<_>>()
+        };
+
+        assert_eq!(keys(JA), keys(EN));
+    }
+
+    #[test]
+    fn falls_back_to_default_locale() {
+        let mut catalog = MessageCatalog::new("en");
+        catalog.insert("fr", "footnote.title".to_string(), "Notes".to_string());
+
+        assert_eq!(catalog.messages(Some("fr")).get("footnote.title"), "Notes");
+        assert_eq!(catalog.messages(Some("fr")).get("footnote.back"), "Back");
+        assert_eq!(catalog.messages(Some("ja")).get("footnote.back"), "戻る");
+        assert_eq!(catalog.messages(None).get("unknown.key"), "unknown.key");
+    }
+}
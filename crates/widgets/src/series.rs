@@ -0,0 +1,77 @@
+use crate::{GroundingWidget, messages::Messages};
+
+#[derive(Clone, Default, Debug)]
+pub struct Series {
+    pub name: String,
+    pub url: String,
+    pub parts: Vec<SeriesPart>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SeriesPart {
+    pub title: String,
+    pub url: String,
+    pub current: bool,
+}
+
+impl GroundingWidget for Series {
+    const TAG: &'static str = "series";
+
+    fn title<'m>(&self, messages: &Messages<'m>) -> (&'m str, &'m str) {
+        (
+            messages.get("series.title"),
+            messages.get("series.subtitle"),
+        )
+    }
+
+    fn build_content(&self, _messages: &Messages) -> Option<String> {
+        if self.parts.is_empty() {
+            return None;
+        }
+
+        let name = &self.name;
+        let url = &self.url;
+
+        Some(format!(
+            r#"<p class="series-name"><a href="{url}">{name}</a></p><ol class="series-parts">{}</ol>"#,
+            self.parts
+                .iter()
+                .map(|part| part.to_html())
+                .collect::<String>(),
+        ))
+    }
+
+    fn content_style(&self) -> &'static str {
+        r#"
+            .series-name {
+                font-weight: bold;
+                margin-bottom: 0.5em;
+            }
+
+            .series-parts {
+                display: flex;
+                flex-direction: column;
+                gap: 0.5em;
+                list-style: decimal inside;
+
+                .current {
+                    color: var(--typed-primary);
+                    font-weight: bold;
+                }
+            }
+        "#
+    }
+}
+
+impl SeriesPart {
+    pub fn to_html(&self) -> String {
+        let title = &self.title;
+        let url = &self.url;
+
+        if self.current {
+            format!(r#"<li class="current" aria-current="page">{title}</li>"#)
+        } else {
+            format!(r#"<li><a href="{url}">{title}</a></li>"#)
+        }
+    }
+}
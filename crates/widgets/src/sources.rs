@@ -1,33 +1,45 @@
 use serde::Deserialize;
 
-use crate::GroundingWidget;
+use crate::{GroundingWidget, messages::Messages};
 
 #[derive(Clone, Default, Deserialize, Debug)]
-pub struct Sources(Vec<Source>);
+pub struct Sources(pub Vec<Source>);
 
-#[derive(Clone, Deserialize, Debug)]
+/// Entry of the sources, from the frontmatter or the bibliography file.
+#[derive(Clone, Default, Deserialize, Debug)]
 pub struct Source {
-    name: String,
-    url: String,
+    /// Cited in the body as `[@key]`.
+    pub key: Option<String>,
+    pub name: String,
+    pub url: Option<String>,
+    #[serde(default)]
+    pub authors: Vec<String>,
+    pub year: Option<i32>,
+    /// The date the URL was accessed.
+    pub accessed: Option<toml::value::Datetime>,
+    pub archive_url: Option<String>,
 }
 
 impl GroundingWidget for Sources {
     const TAG: &'static str = "sources";
 
-    fn title(&self) -> (&'static str, &'static str) {
-        ("ARTICLE SOURCES", "この記事の参考文献")
+    fn title<'m>(&self, messages: &Messages<'m>) -> (&'m str, &'m str) {
+        (
+            messages.get("sources.title"),
+            messages.get("sources.subtitle"),
+        )
     }
 
-    fn build_content(&self) -> Option<String> {
+    fn build_content(&self, messages: &Messages) -> Option<String> {
         if self.0.is_empty() {
             return None;
         }
 
         Some(format!(
-            r#"<ul class="sources-list">{}</ul>"#,
+            r#"<ol class="sources-list">{}</ol>"#,
             self.0
                 .iter()
-                .map(|source| source.to_html())
+                .map(|source| source.to_html(messages))
                 .collect::<String>(),
         ))
     }
@@ -48,20 +60,77 @@ impl GroundingWidget for Sources {
             .name {
                 font-size: 1.25rem;
             }
+
+            .citation a {
+                color: var(--typed-primary);
+            }
         "#
     }
 }
 
 impl Source {
-    pub fn to_html(&self) -> String {
+    pub const FIELDS: &[&str] = &[
+        "key",
+        "name",
+        "url",
+        "authors",
+        "year",
+        "accessed",
+        "archive_url",
+    ];
+
+    pub fn to_html(&self, messages: &Messages) -> String {
+        let id = self
+            .key
+            .as_ref()
+            .map(|key| format!(r#" id="source-{key}""#))
+            .unwrap_or_default();
         let name = &self.name;
-        let url = &self.url;
+        let byline = [
+            (!self.authors.is_empty()).then(|| self.authors.join(", ")),
+            self.year.map(|year| format!("({year})")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        let byline = if byline.is_empty() {
+            String::new()
+        } else {
+            format!(r#"<p class="byline">{}</p>"#, byline.join(" "))
+        };
+        let url = self
+            .url
+            .as_ref()
+            .map(|url| format!(r#"<a href={url}><span>{url}</span></a>"#))
+            .unwrap_or_default();
+        let accessed = self
+            .accessed
+            .map(|accessed| {
+                format!(
+                    r#"<p class="accessed">{}: <time datetime="{accessed}">{accessed}</time></p>"#,
+                    messages.get("sources.accessed")
+                )
+            })
+            .unwrap_or_default();
+        let archive = self
+            .archive_url
+            .as_ref()
+            .map(|archive_url| {
+                format!(
+                    r#"<a class="archive" href={archive_url}>{}</a>"#,
+                    messages.get("sources.archive")
+                )
+            })
+            .unwrap_or_default();
 
         format!(
             r#"
-            <li class="source">
+            <li class="source"{id}>
                 <h4 class="name">{name}</h4>
-                <a href={url}><span>{url}</span></a>
+                {byline}
+                {url}
+                {accessed}
+                {archive}
             </li>
         "#
         )
@@ -0,0 +1,38 @@
+use crate::{Widget, messages::Messages};
+
+/// Opening of a collapsible block written as `:::spoiler [summary]` ... `:::` in the article body.
+#[derive(Clone, Default, Debug)]
+pub struct Spoiler {
+    pub summary: Option<String>,
+}
+
+impl Spoiler {
+    pub const CLOSE: &'static str = "</details>";
+}
+
+impl Widget for Spoiler {
+    const TAG: &'static str = "spoiler";
+
+    fn build(&self, messages: &Messages) -> String {
+        let summary = self
+            .summary
+            .as_deref()
+            .unwrap_or_else(|| messages.get("spoiler.summary"));
+
+        format!(r#"<details class="spoiler"><summary>{summary}</summary>"#)
+    }
+
+    fn style(&self) -> &'static str {
+        r#"
+            .spoiler {
+                border: 1px dashed var(--typed-primary);
+                padding: 6px 12px;
+
+                summary {
+                    cursor: pointer;
+                    color: var(--typed-primary);
+                }
+            }
+        "#
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{Widget, messages::Messages};
+
+/// Notice on the articles not updated for a long time, for the article types with `staleness_notice`.
+#[derive(Clone, Default, Debug)]
+pub struct Staleness {
+    /// Time since the article was written or updated, or `None` if it is within the threshold.
+    pub age: Option<Age>,
+}
+
+/// Age of the article, in days if it is less than a month and in months if it is less than a year.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Age {
+    Days(u32),
+    Months(u32),
+    Years(u32),
+}
+
+impl Age {
+    /// Rounds the days down to 1 to 11 months from 30 days, and to the years from 365 days.
+    pub fn from_days(days: i64) -> Self {
+        let days = days.max(0);
+        let count = |unit: i64| u32::try_from(days / unit).unwrap_or(u32::MAX);
+
+        match days {
+            ..30 => Self::Days(count(1)),
+            30..365 => Self::Months(count(30).min(11)),
+            _ => Self::Years(count(365)),
+        }
+    }
+}
+
+impl Widget for Staleness {
+    const TAG: &'static str = "staleness";
+
+    fn build(&self, messages: &Messages) -> String {
+        let notice = match self.age {
+            Some(Age::Days(days)) => messages
+                .get("staleness.notice_days")
+                .replace("{days}", &days.to_string()),
+            Some(Age::Months(months)) => messages
+                .get("staleness.notice_months")
+                .replace("{months}", &months.to_string()),
+            Some(Age::Years(years)) => messages
+                .get("staleness.notice")
+                .replace("{years}", &years.to_string()),
+            None => return String::new(),
+        };
+
+        format!(
+            r#"
+            <aside class="staleness" role="note">
+                {}
+            </aside>
+        "#,
+            notice
+        )
+    }
+
+    fn style(&self) -> &'static str {
+        r#"
+            .staleness {
+                border: 1px solid var(--typed-primary);
+                padding: 6px 12px;
+            }
+        "#
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Age, Staleness};
+    use crate::{Widget, messages::MessageCatalog};
+
+    #[test]
+    fn warns_only_on_stale_articles() {
+        let catalog = MessageCatalog::new("en");
+        let messages = catalog.messages(None);
+        let staleness = |age| Staleness { age }.build(&messages);
+
+        assert!(staleness(Some(Age::Years(3))).contains("over 3 year(s) old"));
+        assert!(staleness(Some(Age::Months(6))).contains("over 6 month(s) old"));
+        assert!(staleness(Some(Age::Days(20))).contains("20 day(s) old"));
+        assert!(staleness(None).is_empty());
+    }
+
+    #[test]
+    fn rounds_age_down() {
+        assert_eq!(Age::from_days(10), Age::Days(10));
+        assert_eq!(Age::from_days(29), Age::Days(29));
+        assert_eq!(Age::from_days(30), Age::Months(1));
+        assert_eq!(Age::from_days(200), Age::Months(6));
+        assert_eq!(Age::from_days(360), Age::Months(11));
+        assert_eq!(Age::from_days(364), Age::Months(11));
+        assert_eq!(Age::from_days(365), Age::Years(1));
+        assert_eq!(Age::from_days(800), Age::Years(2));
+    }
+}
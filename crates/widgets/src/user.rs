@@ -0,0 +1,193 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use serde::Deserialize;
+
+/// Placeholder names used by the built-in widgets, which the user widgets cannot take.
+pub const RESERVED_NAMES: [&str; 11] = [
+    "ai",
+    "article_type",
+    "type",
+    "sources",
+    "changelog",
+    "series",
+    "authors",
+    "content_warning",
+    "spoiler",
+    "staleness",
+    "bibliography",
+];
+
+/// Widget declared in the configuration.
+///
+/// ```toml
+/// [widgets.callout]
+/// template = "widgets/callout.html"
+/// style = "widgets/callout.css"
+/// fields = { title = "string", level = { type = "integer", optional = true } }
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct UserWidgetDefinition {
+    /// HTML snippet in the template directory, where `${field}` is replaced with the value of the field.
+    pub template: PathBuf,
+    /// CSS in the template directory, added to `${widget_styles}`.
+    pub style: Option<PathBuf>,
+    #[serde(default)]
+    pub fields: BTreeMap<String, FieldSchema>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    String,
+    Integer,
+    Float,
+    Boolean,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum FieldSchema {
+    Required(FieldType),
+    Detailed {
+        #[serde(rename = "type")]
+        field_type: FieldType,
+        #[serde(default)]
+        optional: bool,
+    },
+}
+
+impl FieldSchema {
+    pub fn field_type(&self) -> FieldType {
+        match self {
+            Self::Required(field_type) => *field_type,
+            Self::Detailed { field_type, .. } => *field_type,
+        }
+    }
+
+    pub fn is_optional(&self) -> bool {
+        matches!(self, Self::Detailed { optional: true, .. })
+    }
+}
+
+impl std::fmt::Display for FieldType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::String => write!(f, "a string"),
+            Self::Integer => write!(f, "an integer"),
+            Self::Float => write!(f, "a float"),
+            Self::Boolean => write!(f, "a boolean"),
+        }
+    }
+}
+
+impl FieldType {
+    fn matches(&self, value: &toml::Value) -> bool {
+        matches!(
+            (self, value),
+            (Self::String, toml::Value::String(_))
+                | (Self::Integer, toml::Value::Integer(_))
+                | (Self::Float, toml::Value::Float(_) | toml::Value::Integer(_))
+                | (Self::Boolean, toml::Value::Boolean(_))
+        )
+    }
+}
+
+/// User widget with its snippet and style loaded.
+#[derive(Clone, Debug)]
+pub struct UserWidget {
+    pub name: String,
+    pub html: String,
+    pub style: String,
+    pub fields: BTreeMap<String, FieldSchema>,
+}
+
+impl UserWidget {
+    /// Checks the frontmatter table against the schema, and returns the placeholder contents for the snippet.
+    /// Optional fields without the values are empty.
+    pub fn field_values(
+        &self,
+        table: &toml::Table,
+    ) -> Result<HashMap<String, String>, Vec<String>> {
+        let mut errors = table
+            .keys()
+            .filter(|key| !self.fields.contains_key(key.as_str()))
+            .map(|key| format!("widgets.{}.{key} is not a field of the widget", self.name))
+            .collect::<Vec<_>>();
+
+        let mut values = HashMap::new();
+        for (name, schema) in &self.fields {
+            match table.get(name) {
+                Some(value) if schema.field_type().matches(value) => {
+                    values.insert(name.clone(), field_to_string(value));
+                }
+                Some(_) => errors.push(format!(
+                    "widgets.{}.{name} has to be {}",
+                    self.name,
+                    schema.field_type()
+                )),
+                None if schema.is_optional() => {
+                    values.insert(name.clone(), String::new());
+                }
+                None => errors.push(format!("widgets.{}.{name} is required", self.name)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(values)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn field_to_string(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{FieldSchema, FieldType, UserWidget};
+
+    fn callout() -> UserWidget {
+        UserWidget {
+            name: "callout".to_string(),
+            html: String::new(),
+            style: String::new(),
+            fields: BTreeMap::from([
+                (
+                    "title".to_string(),
+                    FieldSchema::Required(FieldType::String),
+                ),
+                (
+                    "level".to_string(),
+                    FieldSchema::Detailed {
+                        field_type: FieldType::Integer,
+                        optional: true,
+                    },
+                ),
+            ]),
+        }
+    }
+
+    #[test]
+    fn validates_fields() {
+        let values = callout()
+            .field_values(&toml::from_str("title = \"Note\"").expect("valid TOML"))
+            .expect("fields to be valid");
+        assert_eq!(values["title"], "Note");
+        assert_eq!(values["level"], "");
+
+        let errors = callout()
+            .field_values(&toml::from_str("level = \"high\"\ncolor = \"red\"").expect("valid TOML"))
+            .expect_err("fields to be invalid");
+        assert_eq!(errors.len(), 3);
+    }
+}